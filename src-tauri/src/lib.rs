@@ -1,17 +1,210 @@
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::fs;
-use std::path::PathBuf;
-use std::sync::{Arc, Mutex};
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
 use std::thread;
-use tauri::{Manager, State};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::{Emitter, Manager, State};
 
 // Index state
 #[derive(Default)]
 pub struct IndexState {
-    pub entries: Mutex<Vec<IndexEntry>>,
-    pub lower_names: Mutex<Vec<String>>,
+    // Read-mostly: searches take read locks, only index updates take write locks
+    pub entries: RwLock<Vec<IndexEntry>>,
+    pub lower_names: RwLock<Vec<String>>,
     pub progress: Mutex<IndexProgress>,
     pub is_indexing: Mutex<bool>,
+    pub cancel_requested: Mutex<bool>,
+    // Every root the current index was built from
+    pub roots: Mutex<Vec<IndexRoot>>,
+    pub search_history: Mutex<SearchHistory>,
+    pub search_count: AtomicU64,
+    pub total_results: AtomicU64,
+    pub total_latency_ns: AtomicU64,
+    pub zero_result_count: AtomicU64,
+    // Set by in-place index edits, cleared once the index is written to disk
+    pub unsaved_changes: AtomicBool,
+    // Set by recorded searches, cleared once search_history is written to disk
+    pub unsaved_search_history: AtomicBool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SearchHistoryEntry {
+    pub query: String,
+    pub searched_at: u64,
+    pub result_count: usize,
+}
+
+// The folder the index was built from, with its depth cached for path-depth queries
+#[derive(Debug, Clone)]
+pub struct IndexRoot {
+    pub path: PathBuf,
+    pub component_count: usize,
+    // When the full index under this root was built
+    pub indexed_at: u64,
+}
+
+impl IndexRoot {
+    fn new(path: PathBuf, indexed_at: u64) -> Self {
+        let component_count = path.components().count();
+        IndexRoot { path, component_count, indexed_at }
+    }
+}
+
+// The innermost root containing `path`
+fn root_for_path<'a>(roots: &'a [IndexRoot], path: &Path) -> Option<&'a IndexRoot> {
+    roots.iter()
+        .filter(|r| path.starts_with(&r.path))
+        .max_by_key(|r| r.component_count)
+}
+
+// Tab focus history, most recent first
+#[derive(Default)]
+pub struct TabState {
+    pub tab_access_order: Mutex<VecDeque<String>>,
+}
+
+// Every search, most recent first, persisted across sessions
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct SearchHistory {
+    #[serde(default)]
+    pub entries: VecDeque<SearchHistoryEntry>,
+    // Files written before searches were timestamped held bare queries
+    #[serde(default, skip_serializing)]
+    queries: Vec<String>,
+}
+
+impl SearchHistory {
+    fn load() -> Self {
+        let mut history: SearchHistory = fs::read_to_string(get_search_history_path())
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        let legacy = std::mem::take(&mut history.queries);
+        history.entries.extend(legacy.into_iter().map(|query| SearchHistoryEntry {
+            query,
+            searched_at: 0,
+            result_count: 0,
+        }));
+        history
+    }
+
+    fn save(&self) -> Result<(), String> {
+        fs::create_dir_all(get_config_dir())
+            .map_err(|e| format!("Failed to create config directory: {}", e))?;
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize search history: {}", e))?;
+        write_file_atomic(&get_search_history_path(), content.as_bytes())
+            .map_err(|e| format!("Failed to write search history: {}", e))
+    }
+
+    // `distinct_limit` is the search_history_limit preference; the log keeps at least
+    // that many entries so the deduplicated view can fill up
+    fn push(&mut self, entry: SearchHistoryEntry, distinct_limit: usize) {
+        self.entries.push_front(entry);
+        self.entries.truncate(MAX_SEARCH_HISTORY.max(distinct_limit));
+    }
+
+    // Each query once, at its most recent position
+    fn distinct_queries(&self, limit: usize) -> Vec<String> {
+        let mut seen = HashSet::new();
+        self.entries.iter()
+            .filter(|e| seen.insert(e.query.as_str()))
+            .take(limit)
+            .map(|e| e.query.clone())
+            .collect()
+    }
+}
+
+// Visited directories, most recent first; revisiting moves a path back to the front
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct RecentPaths {
+    pub paths: VecDeque<String>,
+}
+
+impl RecentPaths {
+    fn load() -> Self {
+        fs::read_to_string(get_recent_paths_path())
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self) -> Result<(), String> {
+        fs::create_dir_all(get_config_dir())
+            .map_err(|e| format!("Failed to create config directory: {}", e))?;
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize recent paths: {}", e))?;
+        write_file_atomic(&get_recent_paths_path(), content.as_bytes())
+            .map_err(|e| format!("Failed to write recent paths: {}", e))
+    }
+
+    fn push(&mut self, path: &str, limit: usize) {
+        self.paths.retain(|p| p != path);
+        self.paths.push_front(path.to_string());
+        self.paths.truncate(limit);
+    }
+}
+
+// File watcher state, keyed by watched path
+#[derive(Default)]
+pub struct WatcherState {
+    pub watches: Mutex<HashMap<String, WatchInfo>>,
+    // Events received per watched path since it was added (or last reset)
+    pub change_counts: Mutex<HashMap<String, AtomicU64>>,
+    // Keeps the index in sync with the index roots; dropping it stops the watch
+    pub index_watcher: Mutex<Option<notify::RecommendedWatcher>>,
+}
+
+impl WatcherState {
+    // Credit an event to every watch that covers the changed path
+    pub fn record_change(&self, changed: &Path) {
+        let (watches, mut counts) = match (self.watches.lock(), self.change_counts.lock()) {
+            (Ok(w), Ok(c)) => (w, c),
+            _ => return,
+        };
+
+        for (watched, info) in watches.iter() {
+            let covered = changed == Path::new(watched)
+                || changed.parent() == Some(Path::new(watched))
+                || (info.recursive && changed.starts_with(watched));
+            if covered {
+                counts.entry(watched.clone())
+                    .or_default()
+                    .fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct WatchInfo {
+    pub watch_id: String,
+    pub recursive: bool,
+    pub active_since: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WatchStatus {
+    pub path: String,
+    pub is_watched: bool,
+    pub watch_id: Option<String>,
+    pub kind: Option<String>,
+    pub active_since: Option<u64>,
+}
+
+impl WatchStatus {
+    fn from_info(path: &str, info: Option<&WatchInfo>) -> Self {
+        WatchStatus {
+            path: path.to_string(),
+            is_watched: info.is_some(),
+            watch_id: info.map(|i| i.watch_id.clone()),
+            kind: info.map(|i| if i.recursive { "recursive" } else { "non_recursive" }.to_string()),
+            active_since: info.map(|i| i.active_since),
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -20,6 +213,24 @@ pub struct IndexEntry {
     pub path: String,
     pub is_directory: bool,
     pub parent_folder: String,
+    #[serde(default)]
+    pub modified_secs: Option<u64>,
+    #[serde(default)]
+    pub created_secs: Option<u64>,
+    #[serde(default)]
+    pub accessed_secs: Option<u64>,
+    // None for directories
+    #[serde(default)]
+    pub size_bytes: Option<u64>,
+    // Lowercased, without the dot; empty for directories and extensionless files
+    #[serde(default)]
+    pub extension: String,
+    // The index root this entry was found under; empty in indexes saved before multiple roots
+    #[serde(default)]
+    pub root: String,
+    // When this entry entered the index; 0 for entries saved before this was tracked
+    #[serde(default)]
+    pub indexed_at: u64,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
@@ -29,6 +240,8 @@ pub struct IndexProgress {
     pub total_files: usize,
     pub current_folder: String,
     pub is_complete: bool,
+    #[serde(default)]
+    pub last_saved_at: Option<u64>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -36,6 +249,12 @@ pub struct FileEntry {
     pub name: String,
     pub path: String,
     pub is_directory: bool,
+    // None for directories, or when metadata can't be read
+    pub size_bytes: Option<u64>,
+    pub modified_secs: Option<u64>,
+    // Nesting below the listed directory; 0 for its immediate children
+    #[serde(default)]
+    pub depth: u32,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -56,8 +275,64 @@ pub struct Config {
     pub tabs: Option<Vec<Tab>>,
     #[serde(default)]
     pub active_tab_id: Option<String>,
+    // Recording access times costs an extra syscall per entry, so it is opt-in
+    #[serde(default)]
+    pub index_access_times: bool,
+    // Tab IDs, most recently focused first
+    #[serde(default)]
+    pub active_tab_order: Vec<String>,
+    // Missing in files written before versioning, which deserialize as version 0
+    #[serde(default)]
+    pub config_version: u32,
+    // Gitignore-style globs matched against each entry's full path; matching files and
+    // directories are left out of the index. Patterns without a '/' match at any depth
+    // ("*.log"), "**" spans directories ("**/target/**") and a leading "~/" is the home directory.
+    #[serde(default)]
+    pub exclude_patterns: Vec<String>,
+    // Directory names never descended into; None means DEFAULT_SKIP_DIRECTORIES
+    #[serde(default)]
+    pub skip_directories: Option<Vec<String>>,
+    // Directories (absolute, or relative to home) indexed even when skipped or hidden
+    #[serde(default)]
+    pub include_directories: Option<Vec<String>>,
+    // Folders to index; None (or no usable entries) means just the home directory
+    #[serde(default)]
+    pub index_roots: Option<Vec<String>>,
+    // How many distinct queries search history keeps
+    #[serde(default = "default_search_history_limit")]
+    pub search_history_limit: usize,
+    // How many recently visited directories are remembered
+    #[serde(default = "default_recent_paths_limit")]
+    pub recent_paths_limit: usize,
+    // Keep the index in sync with file changes once indexing finishes. Off by default:
+    // a recursive watch of a large root can exhaust the OS watch limit.
+    #[serde(default)]
+    pub watch_enabled: bool,
+    // Whether directory listings include dot-files by default
+    #[serde(default)]
+    pub show_hidden_files: bool,
+    // How often in-place index edits are saved to disk; None or 0 turns auto-save off
+    #[serde(default = "default_auto_save_interval_secs")]
+    pub auto_save_interval_secs: Option<u64>,
+    // Terminal launched by open_in_terminal; None means auto-detect
+    #[serde(default)]
+    pub preferred_terminal: Option<String>,
+}
+
+fn default_search_history_limit() -> usize {
+    50
+}
+
+fn default_recent_paths_limit() -> usize {
+    20
 }
 
+fn default_auto_save_interval_secs() -> Option<u64> {
+    Some(300)
+}
+
+const CONFIG_VERSION: u32 = 1;
+
 impl Default for Config {
     fn default() -> Self {
         Config {
@@ -67,10 +342,31 @@ impl Default for Config {
             last_location: None,
             tabs: None,
             active_tab_id: None,
+            index_access_times: false,
+            active_tab_order: Vec::new(),
+            config_version: CONFIG_VERSION,
+            exclude_patterns: Vec::new(),
+            skip_directories: None,
+            include_directories: None,
+            index_roots: None,
+            search_history_limit: default_search_history_limit(),
+            recent_paths_limit: default_recent_paths_limit(),
+            watch_enabled: false,
+            show_hidden_files: false,
+            auto_save_interval_secs: default_auto_save_interval_secs(),
+            preferred_terminal: None,
         }
     }
 }
 
+fn system_time_secs(time: SystemTime) -> Option<u64> {
+    time.duration_since(UNIX_EPOCH).ok().map(|d| d.as_secs())
+}
+
+fn now_secs() -> u64 {
+    system_time_secs(SystemTime::now()).unwrap_or(0)
+}
+
 fn get_config_dir() -> PathBuf {
     dirs::config_dir()
         .unwrap_or_else(|| PathBuf::from("."))
@@ -81,9 +377,20 @@ fn get_config_path() -> PathBuf {
     get_config_dir().join("config.json")
 }
 
+// Without `include_hidden`, the show_hidden_files preference decides
 #[tauri::command]
-fn read_directory(path: String) -> Result<Vec<FileEntry>, String> {
-    let path = PathBuf::from(&path);
+fn read_directory(path: String, include_hidden: Option<bool>) -> Result<Vec<FileEntry>, String> {
+    let include_hidden = include_hidden.unwrap_or_else(|| load_config().show_hidden_files);
+    let mut entries = list_directory_entries(Path::new(&path), include_hidden)?;
+
+    // Sort: folders first, then files, both alphabetically
+    entries.sort_by(|a, b| dirs_first_order(a.is_directory, &a.name, b.is_directory, &b.name));
+
+    Ok(entries)
+}
+
+// Unsorted children of a directory
+fn list_directory_entries(path: &Path, include_hidden: bool) -> Result<Vec<FileEntry>, String> {
 
     if !path.exists() {
         return Err(format!("Path does not exist: {}", path.display()));
@@ -95,41 +402,159 @@ fn read_directory(path: String) -> Result<Vec<FileEntry>, String> {
 
     let mut entries: Vec<FileEntry> = Vec::new();
 
-    match fs::read_dir(&path) {
+    match fs::read_dir(path) {
         Ok(read_dir) => {
             for entry in read_dir.flatten() {
                 let file_name = entry.file_name().to_string_lossy().to_string();
 
                 // Skip hidden files (starting with .)
-                if file_name.starts_with('.') {
+                if !include_hidden && file_name.starts_with('.') {
                     continue;
                 }
 
                 let file_path = entry.path();
-                let is_dir = file_path.is_dir();
+                // Follows symlinks, so a dangling link simply has no metadata
+                let metadata = fs::metadata(&file_path).ok();
+                let is_dir = metadata.as_ref().map(|m| m.is_dir()).unwrap_or(false);
 
                 entries.push(FileEntry {
                     name: file_name,
                     path: file_path.to_string_lossy().to_string(),
                     is_directory: is_dir,
+                    size_bytes: metadata.as_ref().filter(|m| m.is_file()).map(|m| m.len()),
+                    modified_secs: metadata.as_ref()
+                        .and_then(|m| m.modified().ok())
+                        .and_then(system_time_secs),
+                    depth: 0,
                 });
             }
         }
         Err(e) => return Err(format!("Failed to read directory: {}", e)),
     }
 
-    // Sort: folders first, then files, both alphabetically
-    entries.sort_by(|a, b| {
-        match (a.is_directory, b.is_directory) {
-            (true, false) => std::cmp::Ordering::Less,
-            (false, true) => std::cmp::Ordering::Greater,
-            _ => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+    Ok(entries)
+}
+
+const DEFAULT_MAX_RECURSIVE_ENTRIES: usize = 100_000;
+const TRUNCATED_ENTRY_NAME: &str = "…truncated…";
+
+// Depth-first, each level sorted folders-first, so a directory is followed by its contents.
+// `max_depth` 0 lists only the immediate children. Symlinked directories are listed but
+// not descended into, to avoid cycles.
+#[tauri::command]
+fn read_directory_recursive(
+    path: String,
+    max_depth: Option<u32>,
+    max_entries: Option<usize>,
+    include_hidden: bool,
+) -> Result<Vec<FileEntry>, String> {
+    fn walk(
+        dir: &Path,
+        depth: u32,
+        max_depth: Option<u32>,
+        max_entries: usize,
+        include_hidden: bool,
+        out: &mut Vec<FileEntry>,
+    ) -> Result<bool, String> {
+        let mut children = list_directory_entries(dir, include_hidden)?;
+        children.sort_by(|a, b| dirs_first_order(a.is_directory, &a.name, b.is_directory, &b.name));
+
+        for mut child in children {
+            if out.len() >= max_entries {
+                return Ok(true);
+            }
+            child.depth = depth;
+            let descend = child.is_directory
+                && max_depth.is_none_or(|max| depth < max)
+                && fs::symlink_metadata(&child.path).is_ok_and(|m| m.is_dir());
+            let child_path = PathBuf::from(&child.path);
+            out.push(child);
+
+            // Unreadable subdirectories are listed but left empty
+            if descend && walk(&child_path, depth + 1, max_depth, max_entries, include_hidden, out).unwrap_or(false) {
+                return Ok(true);
+            }
         }
+        Ok(false)
+    }
+
+    let mut entries = Vec::new();
+    let max_entries = max_entries.unwrap_or(DEFAULT_MAX_RECURSIVE_ENTRIES);
+    let truncated = walk(Path::new(&path), 0, max_depth, max_entries, include_hidden, &mut entries)?;
+
+    if truncated {
+        entries.push(FileEntry {
+            name: TRUNCATED_ENTRY_NAME.to_string(),
+            path: String::new(),
+            is_directory: false,
+            size_bytes: None,
+            modified_secs: None,
+            depth: 0,
+        });
+    }
+    Ok(entries)
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum SortMode {
+    Name,
+    Size,
+    Modified,
+    Extension,
+    Type,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum SortDirection {
+    Ascending,
+    Descending,
+}
+
+// Folders stay ahead of files except in Type mode, where the kind is the sort key itself.
+// Ties always fall back to ascending name order. Hidden entries follow read_directory.
+#[tauri::command]
+fn read_directory_sorted(
+    path: String,
+    sort: SortMode,
+    direction: SortDirection,
+    include_hidden: Option<bool>,
+) -> Result<Vec<FileEntry>, String> {
+    use std::cmp::Ordering;
+
+    let include_hidden = include_hidden.unwrap_or_else(|| load_config().show_hidden_files);
+    let mut entries = list_directory_entries(Path::new(&path), include_hidden)?;
+    let reverse = |order: Ordering| match direction {
+        SortDirection::Ascending => order,
+        SortDirection::Descending => order.reverse(),
+    };
+    let by_name = |a: &FileEntry, b: &FileEntry| a.name.to_lowercase().cmp(&b.name.to_lowercase());
+
+    entries.sort_by(|a, b| {
+        let folders_first = b.is_directory.cmp(&a.is_directory);
+        let key = match sort {
+            SortMode::Name => reverse(by_name(a, b)),
+            SortMode::Size => reverse(a.size_bytes.cmp(&b.size_bytes)),
+            SortMode::Modified => reverse(a.modified_secs.cmp(&b.modified_secs)),
+            SortMode::Extension => reverse(entry_extension(&a.name).cmp(&entry_extension(&b.name))),
+            SortMode::Type => {
+                return reverse(folders_first.then_with(|| entry_extension(&a.name).cmp(&entry_extension(&b.name))))
+                    .then_with(|| by_name(a, b));
+            }
+        };
+        folders_first.then(key).then_with(|| by_name(a, b))
     });
 
     Ok(entries)
 }
 
+fn dirs_first_order(a_is_dir: bool, a_name: &str, b_is_dir: bool, b_name: &str) -> std::cmp::Ordering {
+    match (a_is_dir, b_is_dir) {
+        (true, false) => std::cmp::Ordering::Less,
+        (false, true) => std::cmp::Ordering::Greater,
+        _ => a_name.to_lowercase().cmp(&b_name.to_lowercase()),
+    }
+}
+
 #[tauri::command]
 fn get_parent_path(path: String) -> Option<String> {
     PathBuf::from(&path)
@@ -147,27 +572,34 @@ fn get_relative_path(full_path: String, base_path: String) -> String {
         .unwrap_or(full_path)
 }
 
+// Older configs are migrated and written back on first load, keeping the original as a backup
 #[tauri::command]
 fn load_config() -> Config {
-    let config_path = get_config_path();
+    let raw = match read_raw_config() {
+        Some(raw) => raw,
+        None => return Config::default(),
+    };
 
-    if config_path.exists() {
-        match fs::read_to_string(&config_path) {
-            Ok(content) => {
-                serde_json::from_str(&content).unwrap_or_default()
-            }
-            Err(_) => Config::default(),
+    if raw_config_version(&raw) >= CONFIG_VERSION {
+        return serde_json::from_value(raw).unwrap_or_default();
+    }
+
+    match migrate_raw_config(raw) {
+        Ok(config) => {
+            let _ = write_migrated_config(config.clone());
+            config
         }
-    } else {
-        Config::default()
+        Err(_) => Config::default(),
     }
 }
 
-#[tauri::command]
-fn save_config(config: Config) -> Result<(), String> {
+fn write_config(mut config: Config) -> Result<(), String> {
     let config_dir = get_config_dir();
     let config_path = get_config_path();
 
+    // Anything that round-trips through Config already has the current shape
+    config.config_version = CONFIG_VERSION;
+
     // Create config directory if it doesn't exist
     fs::create_dir_all(&config_dir)
         .map_err(|e| format!("Failed to create config directory: {}", e))?;
@@ -175,183 +607,4487 @@ fn save_config(config: Config) -> Result<(), String> {
     let content = serde_json::to_string_pretty(&config)
         .map_err(|e| format!("Failed to serialize config: {}", e))?;
 
-    fs::write(&config_path, content)
+    // load_config sees either the old file or the new one, never a partial write
+    write_file_atomic(&config_path, content.as_bytes())
         .map_err(|e| format!("Failed to write config: {}", e))?;
-
+    // Not yet cached means the first read will pick up this file anyway
+    if let Some(limit) = SEARCH_HISTORY_LIMIT.get() {
+        limit.store(config.search_history_limit, Ordering::Relaxed);
+    }
     Ok(())
 }
 
-#[tauri::command]
-fn get_home_dir() -> Option<String> {
-    dirs::home_dir().map(|p| p.to_string_lossy().to_string())
-}
+// search_history_limit, cached so recording a search doesn't re-read the config file
+static SEARCH_HISTORY_LIMIT: OnceLock<AtomicUsize> = OnceLock::new();
 
-#[tauri::command]
-fn path_exists(path: String) -> bool {
-    PathBuf::from(&path).exists()
+fn search_history_limit() -> usize {
+    SEARCH_HISTORY_LIMIT
+        .get_or_init(|| AtomicUsize::new(load_config().search_history_limit))
+        .load(Ordering::Relaxed)
 }
 
 #[tauri::command]
-async fn toggle_window_visibility(app: tauri::AppHandle) {
-    if let Some(window) = app.get_webview_window("main") {
-        if window.is_visible().unwrap_or(false) {
-            let _ = window.hide();
-        } else {
-            let _ = window.show();
-            let _ = window.set_focus();
-        }
-    }
+fn save_config(config: Config) -> Result<(), String> {
+    write_config(config)
 }
 
-fn get_index_path() -> PathBuf {
-    get_config_dir().join("index.json")
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ConfigError {
+    pub field: String,
+    pub message: String,
+    // Warnings don't stop a save, they only flag something worth a look
+    pub is_warning: bool,
 }
 
-fn index_directory(
-    path: &PathBuf,
-    entries: &mut Vec<IndexEntry>,
-    lower_names: &mut Vec<String>,
-    progress: &Arc<Mutex<IndexProgress>>,
-    skip_hidden: bool,
-) {
-    let dir_entries = match fs::read_dir(path) {
-        Ok(e) => e,
-        Err(_) => return,
+#[tauri::command]
+fn validate_config(config: Config) -> Vec<ConfigError> {
+    let mut errors = Vec::new();
+    let mut report = |field: &str, message: String, is_warning: bool| {
+        errors.push(ConfigError { field: field.to_string(), message, is_warning });
     };
 
-    let parent_folder = path.file_name()
-        .map(|n| n.to_string_lossy().to_string())
-        .unwrap_or_else(|| "~".to_string());
-
-    // Update current folder in progress
-    if let Ok(mut prog) = progress.lock() {
-        prog.current_folder = path.to_string_lossy().to_string();
-    }
-
-    let mut subdirs = Vec::new();
-
-    for entry in dir_entries.flatten() {
-        let name = entry.file_name().to_string_lossy().to_string();
-
-        // Skip hidden files/folders
-        if skip_hidden && name.starts_with('.') {
-            continue;
+    if let Some(root) = &config.root_folder {
+        if !Path::new(root).is_dir() {
+            report("root_folder", format!("Not a directory: {}", root), true);
         }
+    }
 
-        let file_path = entry.path();
-        let is_dir = file_path.is_dir();
-        let name_lower = name.to_lowercase();
-
-        entries.push(IndexEntry {
-            name: name.clone(),
-            path: file_path.to_string_lossy().to_string(),
-            is_directory: is_dir,
-            parent_folder: parent_folder.clone(),
-        });
-        lower_names.push(name_lower);
-
-        // Update total files count less frequently (every 100 files)
-        if entries.len() % 100 == 0 {
-            if let Ok(mut prog) = progress.lock() {
-                prog.total_files = entries.len();
+    // Parsed the same way the global-shortcut plugin parses it on registration
+    if let Some(hotkey) = config.global_hotkey.as_deref().filter(|h| !h.trim().is_empty()) {
+        match hotkey.parse::<tauri_plugin_global_shortcut::Shortcut>() {
+            Ok(shortcut) if shortcut.mods.is_empty() => {
+                report("global_hotkey", "A global hotkey needs at least one modifier key".to_string(), false);
             }
+            Ok(_) => {}
+            Err(e) => report("global_hotkey", format!("Invalid hotkey: {}", e), false),
         }
+    }
 
-        if is_dir {
-            // Skip common large/unneeded directories
-            if !["node_modules", "target", ".git", "dist", "build", ".next", "vendor", "__pycache__", ".venv", "venv", ".cargo", "Library", ".Trash", "Applications"].contains(&name.as_str()) {
-                if let Ok(mut prog) = progress.lock() {
-                    prog.total_folders += 1;
-                }
-                subdirs.push(file_path);
-            }
+    let mut seen_ids = HashSet::new();
+    for tab in config.tabs.iter().flatten() {
+        if !seen_ids.insert(tab.id.as_str()) {
+            report("tabs", format!("Duplicate tab id: {}", tab.id), false);
         }
     }
 
-    // Update indexed folders count
-    if let Ok(mut prog) = progress.lock() {
-        prog.indexed_folders += 1;
-        prog.total_files = entries.len();
+    if let Some(location) = &config.last_location {
+        if !Path::new(location).exists() {
+            report("last_location", format!("Path does not exist: {}", location), false);
+        }
     }
 
-    // Recursively index subdirectories
-    for subdir in subdirs {
-        index_directory(&subdir, entries, lower_names, progress, skip_hidden);
-    }
+    errors
 }
 
+// Bookmarks, search history and recent paths live in their own files and are left alone
 #[tauri::command]
-fn start_indexing(app: tauri::AppHandle) -> Result<(), String> {
-    let state: State<'_, IndexState> = app.state();
-
-    // Check if already indexing
-    {
-        let is_indexing = state.is_indexing.lock().map_err(|e| e.to_string())?;
-        if *is_indexing {
-            return Ok(());
-        }
+fn reset_config(preserve_tabs: bool) -> Result<Config, String> {
+    let mut config = Config::default();
+    if preserve_tabs {
+        let current = load_config();
+        config.tabs = current.tabs;
+        config.active_tab_id = current.active_tab_id;
     }
 
-    // Set indexing flag
-    {
-        let mut is_indexing = state.is_indexing.lock().map_err(|e| e.to_string())?;
-        *is_indexing = true;
-    }
+    write_config(config.clone())?;
+    Ok(config)
+}
 
-    // Get home directory
-    let home_dir = dirs::home_dir().ok_or("Could not find home directory")?;
+// v0 configs predate versioning but already share the v1 shape
+fn migrate_v0_to_v1(_config: &mut serde_json::Value) {}
 
-    // Initialize with the root folder, then increment as subfolders are discovered.
-    let total_folders = 1usize;
-    {
-        let mut progress = state.progress.lock().map_err(|e| e.to_string())?;
-        progress.total_folders = total_folders.max(1);
-    }
+// Ordered migrations: (from_version, description, migration)
+type ConfigMigration = (u32, &'static str, fn(&mut serde_json::Value));
 
-    let app_handle = app.clone();
+const CONFIG_MIGRATIONS: &[ConfigMigration] = &[
+    (0, "Add config versioning", migrate_v0_to_v1),
+];
 
-    thread::spawn(move || {
-        let state: State<'_, IndexState> = app_handle.state();
-        let mut new_entries = Vec::new();
-        let mut new_lower_names = Vec::new();
+fn read_raw_config() -> Option<serde_json::Value> {
+    read_raw_config_from(&get_config_path())
+}
 
-        // Use the state's progress directly wrapped in Arc for the indexing function
-        let progress_arc = Arc::new(Mutex::new(IndexProgress {
-            total_folders,
-            indexed_folders: 0,
-            total_files: 0,
-            current_folder: String::new(),
-            is_complete: false,
-        }));
+fn read_raw_config_from(path: &Path) -> Option<serde_json::Value> {
+    let content = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
 
-        // Spawn a thread to sync progress to state
-        let progress_for_sync = Arc::clone(&progress_arc);
-        let app_for_sync = app_handle.clone();
-        let sync_handle = thread::spawn(move || {
-            loop {
-                thread::sleep(std::time::Duration::from_millis(200));
-                let sync_state: State<'_, IndexState> = app_for_sync.state();
-
-                let is_done = {
-                    if let Ok(prog) = progress_for_sync.lock() {
-                        if let Ok(mut state_prog) = sync_state.progress.lock() {
-                            *state_prog = prog.clone();
-                        }
-                        prog.is_complete
-                    } else {
-                        false
-                    }
-                };
+fn raw_config_version(raw: &serde_json::Value) -> u32 {
+    raw.get("config_version")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0) as u32
+}
 
-                if is_done {
-                    break;
-                }
-            }
-        });
+// Applies every migration from the config's version onwards
+fn migrate_raw_config(mut raw: serde_json::Value) -> Result<Config, String> {
+    let version = raw_config_version(&raw);
+    for (from, _, migration) in CONFIG_MIGRATIONS {
+        if *from >= version {
+            migration(&mut raw);
+        }
+    }
 
-        index_directory(&home_dir, &mut new_entries, &mut new_lower_names, &progress_arc, true);
+    serde_json::from_value(raw).map_err(|e| format!("Failed to parse migrated config: {}", e))
+}
 
-        let total_files = new_entries.len();
+fn get_config_backup_path() -> PathBuf {
+    get_config_path().with_extension("json.bak")
+}
+
+// Copies the pre-migration file aside before it's overwritten with the new version
+fn write_migrated_config(config: Config) -> Result<(), String> {
+    fs::copy(get_config_path(), get_config_backup_path())
+        .map_err(|e| format!("Failed to back up config: {}", e))?;
+    write_config(config)
+}
+
+#[tauri::command]
+fn get_config_version() -> u32 {
+    load_config().config_version
+}
+
+#[tauri::command]
+fn get_config_migration_needed() -> Option<String> {
+    let version = raw_config_version(&read_raw_config()?);
+    let pending: Vec<&str> = CONFIG_MIGRATIONS.iter()
+        .filter(|(from, _, _)| *from >= version)
+        .map(|(_, description, _)| *description)
+        .collect();
+
+    if pending.is_empty() {
+        None
+    } else {
+        Some(format!("Config v{} needs migrating to v{}: {}", version, CONFIG_VERSION, pending.join("; ")))
+    }
+}
+
+#[tauri::command]
+fn migrate_config() -> Result<(), String> {
+    let raw = read_raw_config().ok_or("No readable config to migrate")?;
+    // Re-running on a current config would replace the pre-migration backup
+    if raw_config_version(&raw) >= CONFIG_VERSION {
+        return Ok(());
+    }
+    write_migrated_config(migrate_raw_config(raw)?)
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ConfigDiffEntry {
+    pub field: String,
+    pub current_value: serde_json::Value,
+    pub other_value: serde_json::Value,
+}
+
+#[tauri::command]
+fn get_config_diff(other_path: String) -> Result<Vec<ConfigDiffEntry>, String> {
+    let content = fs::read_to_string(&other_path)
+        .map_err(|e| format!("Failed to read config: {}", e))?;
+    // Going through Config fills in defaults, so missing fields don't show up as spurious changes
+    let other: Config = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse config: {}", e))?;
+
+    let current = serde_json::to_value(load_config())
+        .map_err(|e| format!("Failed to serialize config: {}", e))?;
+    let other = serde_json::to_value(other)
+        .map_err(|e| format!("Failed to serialize config: {}", e))?;
+
+    let (current, other) = match (current, other) {
+        (serde_json::Value::Object(c), serde_json::Value::Object(o)) => (c, o),
+        _ => return Err("Config is not an object".to_string()),
+    };
+
+    let fields: std::collections::BTreeSet<&String> = current.keys().chain(other.keys()).collect();
+    Ok(fields.into_iter()
+        .filter_map(|field| {
+            let current_value = current.get(field).cloned().unwrap_or(serde_json::Value::Null);
+            let other_value = other.get(field).cloned().unwrap_or(serde_json::Value::Null);
+            (current_value != other_value).then(|| ConfigDiffEntry {
+                field: field.clone(),
+                current_value,
+                other_value,
+            })
+        })
+        .collect())
+}
+
+#[tauri::command]
+fn get_home_dir() -> Option<String> {
+    dirs::home_dir().map(|p| p.to_string_lossy().to_string())
+}
+
+#[tauri::command]
+fn get_cwd() -> String {
+    std::env::current_dir()
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_else(|_| ".".to_string())
+}
+
+#[tauri::command]
+fn set_cwd(path: String) -> Result<(), String> {
+    std::env::set_current_dir(&path)
+        .map_err(|e| format!("Failed to change working directory: {}", e))
+}
+
+#[tauri::command]
+fn path_exists(path: String) -> bool {
+    PathBuf::from(&path).exists()
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PathInfo {
+    // Whether the path resolves; false for a broken symlink
+    pub exists: bool,
+    pub is_symlink: bool,
+    pub canonical: Option<String>,
+    pub symlink_target: Option<String>,
+    pub is_file: bool,
+    pub is_dir: bool,
+}
+
+#[tauri::command]
+fn path_info(path: String) -> Result<PathInfo, String> {
+    if path.is_empty() {
+        return Err("Path is empty".to_string());
+    }
+    let target = PathBuf::from(&path);
+
+    let is_symlink = fs::symlink_metadata(&target)
+        .map(|m| m.file_type().is_symlink())
+        .unwrap_or(false);
+    // Follows symlinks, so a broken one has no metadata
+    let metadata = fs::metadata(&target).ok();
+
+    Ok(PathInfo {
+        exists: metadata.is_some(),
+        is_symlink,
+        canonical: fs::canonicalize(&target).ok().map(|p| p.to_string_lossy().to_string()),
+        symlink_target: if is_symlink {
+            fs::read_link(&target).ok().map(|t| t.to_string_lossy().to_string())
+        } else {
+            None
+        },
+        is_file: metadata.as_ref().is_some_and(|m| m.is_file()),
+        is_dir: metadata.as_ref().is_some_and(|m| m.is_dir()),
+    })
+}
+
+#[tauri::command]
+fn get_path_type(path: String) -> String {
+    let path = PathBuf::from(&path);
+    let metadata = match fs::symlink_metadata(&path) {
+        Ok(m) => m,
+        Err(_) => return "unknown".to_string(),
+    };
+    let file_type = metadata.file_type();
+
+    let kind = if file_type.is_symlink() {
+        match fs::metadata(&path) {
+            Ok(target) if target.is_dir() => "symlink_to_dir",
+            Ok(_) => "symlink_to_file",
+            Err(_) => "broken_symlink",
+        }
+    } else if file_type.is_dir() {
+        "directory"
+    } else if file_type.is_file() {
+        "regular_file"
+    } else {
+        special_file_type(&file_type)
+    };
+
+    kind.to_string()
+}
+
+#[cfg(unix)]
+fn special_file_type(file_type: &fs::FileType) -> &'static str {
+    use std::os::unix::fs::FileTypeExt;
+
+    if file_type.is_block_device() {
+        "block_device"
+    } else if file_type.is_char_device() {
+        "char_device"
+    } else if file_type.is_fifo() {
+        "fifo"
+    } else if file_type.is_socket() {
+        "socket"
+    } else {
+        "unknown"
+    }
+}
+
+// Windows has no device, fifo or socket entries on a regular filesystem
+#[cfg(windows)]
+fn special_file_type(_file_type: &fs::FileType) -> &'static str {
+    "unknown"
+}
+
+// Candidate quick-access folders under home, whether or not they exist
+fn well_known_home_dirs(home: &Path) -> Vec<PathBuf> {
+    // dirs reads the XDG user-dirs config on Linux, which covers localized folder names
+    let well_known: [(&str, Option<PathBuf>); 6] = [
+        ("XDG_DESKTOP_DIR", dirs::desktop_dir()),
+        ("XDG_DOCUMENTS_DIR", dirs::document_dir()),
+        ("XDG_DOWNLOAD_DIR", dirs::download_dir()),
+        ("XDG_PICTURES_DIR", dirs::picture_dir()),
+        ("XDG_MUSIC_DIR", dirs::audio_dir()),
+        ("XDG_VIDEOS_DIR", dirs::video_dir()),
+    ];
+
+    let mut candidates: Vec<PathBuf> = well_known
+        .into_iter()
+        .filter_map(|(xdg_var, resolved)| {
+            // An explicit environment variable wins over the config file
+            std::env::var(xdg_var)
+                .ok()
+                .map(|v| PathBuf::from(v.replace("$HOME", &home.to_string_lossy())))
+                .or(resolved)
+        })
+        .collect();
+    candidates.extend(["Projects", "Code", "work"].iter().map(|name| home.join(name)));
+    candidates
+}
+
+#[tauri::command]
+fn get_home_subdirectories() -> Vec<FileEntry> {
+    let home = match dirs::home_dir() {
+        Some(h) => h,
+        None => return Vec::new(),
+    };
+
+    let mut seen = HashSet::new();
+    well_known_home_dirs(&home).into_iter()
+        .filter(|p| p.is_dir() && seen.insert(p.clone()))
+        .map(|p| FileEntry {
+            name: p.file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            path: p.to_string_lossy().to_string(),
+            is_directory: true,
+            size_bytes: None,
+            modified_secs: fs::metadata(&p).ok()
+                .and_then(|m| m.modified().ok())
+                .and_then(system_time_secs),
+            depth: 0,
+        })
+        .collect()
+}
+
+#[tauri::command]
+fn get_path_relative_to_home(path: String) -> String {
+    let home = match dirs::home_dir() {
+        Some(h) => h,
+        None => return path,
+    };
+
+    match Path::new(&path).strip_prefix(&home) {
+        Ok(rest) if rest.as_os_str().is_empty() => "~".to_string(),
+        Ok(rest) => Path::new("~").join(rest).to_string_lossy().to_string(),
+        Err(_) => path,
+    }
+}
+
+#[tauri::command]
+fn get_path_display_name(path: String) -> String {
+    let target = Path::new(&path);
+
+    if target.has_root() && target.components().count() == 1 {
+        return "Root".to_string();
+    }
+
+    if let Some(home) = dirs::home_dir() {
+        if target == home {
+            return "Home".to_string();
+        }
+        // Well-known folders are recognizable by name alone
+        if well_known_home_dirs(&home).iter().any(|p| p == target) {
+            if let Some(name) = target.file_name() {
+                return name.to_string_lossy().to_string();
+            }
+        }
+    }
+
+    get_path_relative_to_home(path)
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TempDirContents {
+    pub path: String,
+    pub entries: Vec<FileEntry>,
+    pub total_size_bytes: u64,
+}
+
+// Recursively sum file sizes under a directory, without following symlinks
+fn dir_size_bytes(path: &PathBuf) -> u64 {
+    let mut total = 0;
+    if let Ok(read_dir) = fs::read_dir(path) {
+        for entry in read_dir.flatten() {
+            let metadata = match entry.metadata() {
+                Ok(m) => m,
+                Err(_) => continue,
+            };
+            if metadata.is_dir() {
+                total += dir_size_bytes(&entry.path());
+            } else if metadata.is_file() {
+                total += metadata.len();
+            }
+        }
+    }
+    total
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct DirSizeResult {
+    pub total_bytes: u64,
+    pub file_count: usize,
+    // Subdirectories, not counting the directory itself
+    pub dir_count: usize,
+}
+
+// Symlinks are neither followed nor counted, so link cycles can't trap the walk.
+// Unreadable subdirectories are skipped.
+#[tauri::command]
+async fn compute_directory_size(app: tauri::AppHandle, path: String) -> Result<DirSizeResult, String> {
+    const PROGRESS_INTERVAL_FILES: usize = 1000;
+
+    let root = PathBuf::from(&path);
+    if !fs::symlink_metadata(&root).map_err(|e| format!("Failed to read metadata: {}", e))?.is_dir() {
+        return Err(format!("Path is not a directory: {}", root.display()));
+    }
+
+    let walk = move || {
+        let mut result = DirSizeResult::default();
+        let mut pending = vec![root];
+
+        while let Some(dir) = pending.pop() {
+            let read_dir = match fs::read_dir(&dir) {
+                Ok(read_dir) => read_dir,
+                Err(_) => continue,
+            };
+            for entry in read_dir.flatten() {
+                // DirEntry::file_type doesn't follow symlinks
+                let file_type = match entry.file_type() {
+                    Ok(t) => t,
+                    Err(_) => continue,
+                };
+                if file_type.is_dir() {
+                    result.dir_count += 1;
+                    pending.push(entry.path());
+                } else if file_type.is_file() {
+                    result.file_count += 1;
+                    result.total_bytes += entry.metadata().map(|m| m.len()).unwrap_or(0);
+                    if result.file_count.is_multiple_of(PROGRESS_INTERVAL_FILES) {
+                        let _ = app.emit("dir-size-progress", serde_json::json!({
+                            "path": path,
+                            "total_bytes": result.total_bytes,
+                            "file_count": result.file_count,
+                            "dir_count": result.dir_count,
+                        }));
+                    }
+                }
+            }
+        }
+        result
+    };
+
+    tauri::async_runtime::spawn_blocking(walk)
+        .await
+        .map_err(|e| format!("Directory size walk failed: {}", e))
+}
+
+// Count entries (files and directories) and sum file sizes under a directory
+fn dir_entry_stats(path: &Path) -> (u64, u64) {
+    let mut entry_count = 0;
+    let mut total_bytes = 0;
+    if let Ok(read_dir) = fs::read_dir(path) {
+        for entry in read_dir.flatten() {
+            let metadata = match entry.metadata() {
+                Ok(m) => m,
+                Err(_) => continue,
+            };
+            entry_count += 1;
+            if metadata.is_dir() {
+                let (child_count, child_bytes) = dir_entry_stats(&entry.path());
+                entry_count += child_count;
+                total_bytes += child_bytes;
+            } else if metadata.is_file() {
+                total_bytes += metadata.len();
+            }
+        }
+    }
+    (entry_count, total_bytes)
+}
+
+#[derive(Debug, Clone, Copy)]
+struct CloneThroughput {
+    bytes_per_sec: f64,
+    per_file_overhead_secs: f64,
+}
+
+const CLONE_THROUGHPUT_TTL: Duration = Duration::from_secs(5 * 60);
+
+static CLONE_THROUGHPUT: OnceLock<Mutex<Option<(Instant, CloneThroughput)>>> = OnceLock::new();
+
+// Time small writes and empty file creation in a scratch directory under the temp dir
+fn measure_clone_throughput() -> Result<CloneThroughput, String> {
+    const SAMPLE_WRITES: usize = 10;
+    const SAMPLE_BYTES: usize = 64 * 1024;
+    const SAMPLE_CREATES: usize = 100;
+
+    let scratch = std::env::temp_dir().join(format!("spyglass-clone-probe-{}", std::process::id()));
+    fs::create_dir_all(&scratch).map_err(|e| format!("Failed to create probe directory: {}", e))?;
+
+    let measure = || -> Result<CloneThroughput, String> {
+        let payload = vec![0u8; SAMPLE_BYTES];
+        let started = Instant::now();
+        for i in 0..SAMPLE_WRITES {
+            fs::write(scratch.join(format!("write-{}", i)), &payload)
+                .map_err(|e| format!("Failed to write probe file: {}", e))?;
+        }
+        let write_secs = started.elapsed().as_secs_f64();
+
+        let started = Instant::now();
+        for i in 0..SAMPLE_CREATES {
+            fs::File::create(scratch.join(format!("empty-{}", i)))
+                .map_err(|e| format!("Failed to create probe file: {}", e))?;
+        }
+        let create_secs = started.elapsed().as_secs_f64();
+
+        let per_file_overhead_secs = create_secs / SAMPLE_CREATES as f64;
+        // Take the creation overhead out of the write timing so it isn't counted twice
+        let transfer_secs = (write_secs - per_file_overhead_secs * SAMPLE_WRITES as f64)
+            .max(f64::EPSILON);
+
+        Ok(CloneThroughput {
+            bytes_per_sec: (SAMPLE_WRITES * SAMPLE_BYTES) as f64 / transfer_secs,
+            per_file_overhead_secs,
+        })
+    };
+
+    let result = measure();
+    let _ = fs::remove_dir_all(&scratch);
+    result
+}
+
+fn clone_throughput() -> Result<CloneThroughput, String> {
+    let cache = CLONE_THROUGHPUT.get_or_init(|| Mutex::new(None));
+    if let Some((measured_at, throughput)) = *cache.lock().map_err(|e| e.to_string())? {
+        if measured_at.elapsed() < CLONE_THROUGHPUT_TTL {
+            return Ok(throughput);
+        }
+    }
+
+    let throughput = measure_clone_throughput()?;
+    // A poisoned cache only costs a re-measurement next time
+    if let Ok(mut cached) = cache.lock() {
+        *cached = Some((Instant::now(), throughput));
+    }
+    Ok(throughput)
+}
+
+#[tauri::command]
+fn get_directory_clone_time_estimate(path: String) -> Result<f64, String> {
+    let dir = PathBuf::from(&path);
+    if !dir.is_dir() {
+        return Err(format!("Not a directory: {}", path));
+    }
+
+    let throughput = clone_throughput()?;
+    let (entry_count, total_bytes) = dir_entry_stats(&dir);
+
+    Ok(entry_count as f64 * throughput.per_file_overhead_secs
+        + total_bytes as f64 / throughput.bytes_per_sec)
+}
+
+#[tauri::command]
+fn get_temp_dir_contents() -> Result<TempDirContents, String> {
+    let temp_dir = std::env::temp_dir();
+    let entries = read_directory(temp_dir.to_string_lossy().to_string(), Some(false))?;
+
+    Ok(TempDirContents {
+        path: temp_dir.to_string_lossy().to_string(),
+        entries,
+        total_size_bytes: dir_size_bytes(&temp_dir),
+    })
+}
+
+// Only direct children of `temp_dir` qualify. The last component must be a plain name, so
+// "temp/link/.." can't climb out; the entry itself is never followed.
+fn resolve_temp_entry(temp_dir: &Path, target: &Path) -> Result<PathBuf, String> {
+    let name = match target.components().next_back() {
+        Some(Component::Normal(name)) => name,
+        _ => return Err(format!("Not a file or folder: {}", target.display())),
+    };
+    let temp_dir = fs::canonicalize(temp_dir)
+        .map_err(|e| format!("Failed to resolve temp directory: {}", e))?;
+    let parent = target.parent()
+        .and_then(|p| fs::canonicalize(p).ok())
+        .ok_or_else(|| format!("Path has no parent: {}", target.display()))?;
+    if parent != temp_dir {
+        return Err(format!("Path is not inside the temp directory: {}", target.display()));
+    }
+    Ok(parent.join(name))
+}
+
+fn remove_temp_entry(temp_dir: &Path, target: &Path) -> Result<(), String> {
+    let resolved = resolve_temp_entry(temp_dir, target)?;
+    // symlink_metadata, so a symlink is unlinked rather than followed
+    let metadata = fs::symlink_metadata(&resolved)
+        .map_err(|e| format!("Failed to read metadata: {}", e))?;
+    if metadata.is_dir() {
+        fs::remove_dir_all(&resolved)
+    } else {
+        fs::remove_file(&resolved)
+    }
+    .map_err(|e| format!("Failed to delete temp file: {}", e))
+}
+
+#[tauri::command]
+fn clear_temp_file(path: String) -> Result<(), String> {
+    remove_temp_entry(&std::env::temp_dir(), Path::new(&path))
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PathAccessTime {
+    pub accessed_secs: u64,
+    pub may_be_stale: bool,
+}
+
+// A single line of /proc/mounts
+#[cfg(target_os = "linux")]
+struct ProcMount {
+    device: String,
+    mount_path: String,
+    fs_type: String,
+    options: Vec<String>,
+}
+
+// /proc/mounts escapes whitespace and backslashes as octal sequences (e.g. \040)
+#[cfg(target_os = "linux")]
+fn unescape_mount_field(field: &str) -> String {
+    let bytes = field.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'\\' && i + 3 < bytes.len() {
+            let code = std::str::from_utf8(&bytes[i + 1..i + 4])
+                .ok()
+                .and_then(|digits| u8::from_str_radix(digits, 8).ok());
+            if let Some(code) = code {
+                out.push(code);
+                i += 4;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).to_string()
+}
+
+#[cfg(target_os = "linux")]
+fn read_proc_mounts() -> Vec<ProcMount> {
+    let content = match fs::read_to_string("/proc/mounts") {
+        Ok(c) => c,
+        Err(_) => return Vec::new(),
+    };
+
+    content.lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let device = unescape_mount_field(fields.next()?);
+            let mount_path = unescape_mount_field(fields.next()?);
+            let fs_type = fields.next()?.to_string();
+            let options = fields.next()?.split(',').map(|o| o.to_string()).collect();
+            Some(ProcMount { device, mount_path, fs_type, options })
+        })
+        .collect()
+}
+
+// Find the mount with the longest mount path that contains the given path
+#[cfg(target_os = "linux")]
+fn find_proc_mount(path: &std::path::Path) -> Option<ProcMount> {
+    let canonical = fs::canonicalize(path).ok()?;
+    read_proc_mounts()
+        .into_iter()
+        .filter(|m| canonical.starts_with(&m.mount_path))
+        .max_by_key(|m| m.mount_path.len())
+}
+
+#[tauri::command]
+fn get_path_access_time(path: String) -> Result<PathAccessTime, String> {
+    let path = PathBuf::from(&path);
+    let metadata = fs::metadata(&path)
+        .map_err(|e| format!("Failed to read metadata: {}", e))?;
+    let accessed = metadata.accessed()
+        .map_err(|e| format!("Access time not available: {}", e))?;
+    let accessed_secs = system_time_secs(accessed)
+        .ok_or("Access time is before the Unix epoch")?;
+
+    // relatime (the Linux default) and noatime only update atime occasionally or never
+    #[cfg(target_os = "linux")]
+    let may_be_stale = find_proc_mount(&path)
+        .map(|m| m.options.iter().any(|o| o == "relatime" || o == "noatime"))
+        .unwrap_or(true);
+    #[cfg(not(target_os = "linux"))]
+    let may_be_stale = false;
+
+    Ok(PathAccessTime { accessed_secs, may_be_stale })
+}
+
+// When atime equals mtime the file was most likely last touched by a write,
+// which is all a noatime/relatime mount can tell us
+#[tauri::command]
+fn get_file_last_access_delta(path: String) -> Result<u64, String> {
+    let metadata = fs::metadata(&path)
+        .map_err(|e| format!("Failed to read metadata: {}", e))?;
+
+    // Directory atime changes on every listing, so it carries no signal
+    if metadata.is_dir() {
+        return Ok(0);
+    }
+
+    let accessed = metadata.accessed()
+        .ok()
+        .and_then(system_time_secs)
+        .ok_or("Access time not available")?;
+    Ok(now_secs().saturating_sub(accessed))
+}
+
+#[tauri::command]
+fn get_file_age_secs(path: String) -> Result<u64, String> {
+    let modified = fs::metadata(&path)
+        .map_err(|e| format!("Failed to read metadata: {}", e))?
+        .modified()
+        .ok()
+        .and_then(system_time_secs)
+        .ok_or("Modification time not available")?;
+    Ok(now_secs().saturating_sub(modified))
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DirTimestamps {
+    pub path: String,
+    pub created_secs: Option<u64>,
+    pub modified_secs: u64,
+    pub accessed_secs: Option<u64>,
+}
+
+#[tauri::command]
+fn get_directory_timestamps(path: String) -> Result<DirTimestamps, String> {
+    let dir = PathBuf::from(&path);
+    let metadata = fs::metadata(&dir)
+        .map_err(|e| format!("Failed to read metadata: {}", e))?;
+
+    if !metadata.is_dir() {
+        return Err(format!("Path is not a directory: {}", dir.display()));
+    }
+
+    let modified_secs = metadata.modified()
+        .ok()
+        .and_then(system_time_secs)
+        .ok_or("Modification time not available")?;
+
+    Ok(DirTimestamps {
+        path,
+        created_secs: metadata.created().ok().and_then(system_time_secs),
+        modified_secs,
+        accessed_secs: metadata.accessed().ok().and_then(system_time_secs),
+    })
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DirectoryMetadata {
+    pub entry_count: usize,
+    // Immediate child files only; subdirectories are not walked
+    pub total_size_bytes: u64,
+    pub last_modified_secs: Option<u64>,
+    pub is_readable: bool,
+}
+
+// Counts what read_directory would list, so hidden entries are left out
+#[tauri::command]
+fn get_directory_metadata(path: String) -> Result<DirectoryMetadata, String> {
+    let dir = PathBuf::from(&path);
+    let metadata = fs::metadata(&dir)
+        .map_err(|e| format!("Failed to read metadata: {}", e))?;
+
+    if !metadata.is_dir() {
+        return Err(format!("Path is not a directory: {}", dir.display()));
+    }
+
+    let mut entry_count = 0;
+    let mut total_size_bytes = 0;
+    let is_readable = match fs::read_dir(&dir) {
+        Ok(read_dir) => {
+            for entry in read_dir.flatten() {
+                if entry.file_name().to_string_lossy().starts_with('.') {
+                    continue;
+                }
+                entry_count += 1;
+                if let Ok(child) = fs::metadata(entry.path()) {
+                    if child.is_file() {
+                        total_size_bytes += child.len();
+                    }
+                }
+            }
+            true
+        }
+        Err(_) => false,
+    };
+
+    Ok(DirectoryMetadata {
+        entry_count,
+        total_size_bytes,
+        last_modified_secs: metadata.modified().ok().and_then(system_time_secs),
+        is_readable,
+    })
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ExtendedAttributes {
+    pub raw: HashMap<String, Vec<u8>>,
+    // Only the attributes whose values are valid UTF-8
+    pub text: HashMap<String, String>,
+}
+
+#[cfg(unix)]
+fn read_extended_attributes(path: &std::path::Path) -> HashMap<String, Vec<u8>> {
+    let mut attrs = HashMap::new();
+
+    // Filesystems without xattr support fail to list; treat that as "no attributes"
+    let names = match xattr::list(path) {
+        Ok(names) => names,
+        Err(_) => return attrs,
+    };
+
+    for name in names {
+        if let Ok(Some(value)) = xattr::get(path, &name) {
+            attrs.insert(name.to_string_lossy().to_string(), value);
+        }
+    }
+    attrs
+}
+
+// On Windows, alternate data streams play the role of extended attributes
+#[cfg(windows)]
+fn to_wide_null(s: &std::ffi::OsStr) -> Vec<u16> {
+    use std::os::windows::ffi::OsStrExt;
+    s.encode_wide().chain(Some(0)).collect()
+}
+
+#[cfg(windows)]
+fn from_wide_null(buf: &[u16]) -> String {
+    let len = buf.iter().position(|&c| c == 0).unwrap_or(buf.len());
+    String::from_utf16_lossy(&buf[..len])
+}
+
+#[cfg(windows)]
+fn read_extended_attributes(path: &std::path::Path) -> HashMap<String, Vec<u8>> {
+    use windows_sys::Win32::Foundation::INVALID_HANDLE_VALUE;
+    use windows_sys::Win32::Storage::FileSystem::{
+        FindClose, FindFirstStreamW, FindNextStreamW, FindStreamInfoStandard,
+        WIN32_FIND_STREAM_DATA,
+    };
+
+    let mut attrs = HashMap::new();
+    let wide = to_wide_null(path.as_os_str());
+    let mut data: WIN32_FIND_STREAM_DATA = unsafe { std::mem::zeroed() };
+
+    let handle = unsafe {
+        FindFirstStreamW(
+            wide.as_ptr(),
+            FindStreamInfoStandard,
+            &mut data as *mut WIN32_FIND_STREAM_DATA as *mut _,
+            0,
+        )
+    };
+    if handle == INVALID_HANDLE_VALUE {
+        return attrs;
+    }
+
+    loop {
+        let stream_name = from_wide_null(&data.cStreamName);
+
+        // Stream names look like ":Zone.Identifier:$DATA"; the unnamed default stream is "::$DATA"
+        if let Some(name) = stream_name.strip_prefix(':').and_then(|n| n.strip_suffix(":$DATA")) {
+            if !name.is_empty() {
+                let stream_path = format!("{}:{}", path.display(), name);
+                if let Ok(value) = fs::read(&stream_path) {
+                    attrs.insert(name.to_string(), value);
+                }
+            }
+        }
+
+        let found_next = unsafe {
+            FindNextStreamW(handle, &mut data as *mut WIN32_FIND_STREAM_DATA as *mut _)
+        };
+        if found_next == 0 {
+            break;
+        }
+    }
+
+    unsafe { FindClose(handle) };
+    attrs
+}
+
+#[tauri::command]
+fn get_extended_attributes(path: String) -> Result<ExtendedAttributes, String> {
+    let path = PathBuf::from(&path);
+    fs::symlink_metadata(&path)
+        .map_err(|e| format!("Failed to read metadata: {}", e))?;
+
+    let raw = read_extended_attributes(&path);
+    let text = raw.iter()
+        .filter_map(|(name, value)| {
+            std::str::from_utf8(value).ok().map(|v| (name.clone(), v.to_string()))
+        })
+        .collect();
+
+    Ok(ExtendedAttributes { raw, text })
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct SecurityContext {
+    pub selinux_label: Option<String>,
+    pub apparmor_profile: Option<String>,
+    pub capabilities: Vec<String>,
+}
+
+// Indexed by capability bit number, as in linux/capability.h
+#[cfg(target_os = "linux")]
+const LINUX_CAPABILITY_NAMES: [&str; 41] = [
+    "CAP_CHOWN", "CAP_DAC_OVERRIDE", "CAP_DAC_READ_SEARCH", "CAP_FOWNER", "CAP_FSETID",
+    "CAP_KILL", "CAP_SETGID", "CAP_SETUID", "CAP_SETPCAP", "CAP_LINUX_IMMUTABLE",
+    "CAP_NET_BIND_SERVICE", "CAP_NET_BROADCAST", "CAP_NET_ADMIN", "CAP_NET_RAW", "CAP_IPC_LOCK",
+    "CAP_IPC_OWNER", "CAP_SYS_MODULE", "CAP_SYS_RAWIO", "CAP_SYS_CHROOT", "CAP_SYS_PTRACE",
+    "CAP_SYS_PACCT", "CAP_SYS_ADMIN", "CAP_SYS_BOOT", "CAP_SYS_NICE", "CAP_SYS_RESOURCE",
+    "CAP_SYS_TIME", "CAP_SYS_TTY_CONFIG", "CAP_MKNOD", "CAP_LEASE", "CAP_AUDIT_WRITE",
+    "CAP_AUDIT_CONTROL", "CAP_SETFCAP", "CAP_MAC_OVERRIDE", "CAP_MAC_ADMIN", "CAP_SYSLOG",
+    "CAP_WAKE_ALARM", "CAP_BLOCK_SUSPEND", "CAP_AUDIT_READ", "CAP_PERFMON", "CAP_BPF",
+    "CAP_CHECKPOINT_RESTORE",
+];
+
+// Decode the permitted set from a vfs_cap_data blob (magic, then low/high u32 words)
+#[cfg(target_os = "linux")]
+fn decode_file_capabilities(data: &[u8]) -> Vec<String> {
+    let word = |offset: usize| -> u64 {
+        data.get(offset..offset + 4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as u64)
+            .unwrap_or(0)
+    };
+    // Layout: magic_etc, permitted[0], inheritable[0], permitted[1], inheritable[1]
+    let permitted = word(4) | (word(12) << 32);
+
+    (0..LINUX_CAPABILITY_NAMES.len())
+        .filter(|bit| permitted & (1u64 << bit) != 0)
+        .map(|bit| LINUX_CAPABILITY_NAMES[bit].to_string())
+        .collect()
+}
+
+#[cfg(target_os = "linux")]
+fn read_security_context(path: &Path) -> SecurityContext {
+    let selinux_label = xattr::get(path, "security.selinux").ok()
+        .flatten()
+        .map(|v| String::from_utf8_lossy(&v).trim_end_matches('\0').to_string());
+
+    // AppArmor labels processes, not files, so this is the profile spyglass runs under
+    let apparmor_profile = fs::read_to_string("/proc/self/attr/current").ok()
+        .map(|s| s.trim_end_matches(['\0', '\n']).to_string())
+        .filter(|s| !s.is_empty());
+
+    let capabilities = xattr::get(path, "security.capability").ok()
+        .flatten()
+        .map(|v| decode_file_capabilities(&v))
+        .unwrap_or_default();
+
+    SecurityContext { selinux_label, apparmor_profile, capabilities }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_security_context(_path: &Path) -> SecurityContext {
+    SecurityContext::default()
+}
+
+#[tauri::command]
+fn get_path_security_context(path: String) -> Result<SecurityContext, String> {
+    let path = PathBuf::from(&path);
+    fs::symlink_metadata(&path)
+        .map_err(|e| format!("Failed to read metadata: {}", e))?;
+
+    Ok(read_security_context(&path))
+}
+
+// A path split into platform-independent parts, for converting between formats
+struct PathParts {
+    drive: Option<char>,
+    unc: Option<(String, String)>,
+    absolute: bool,
+    components: Vec<String>,
+}
+
+fn split_path_parts(path: &str) -> PathParts {
+    let normalized = path.replace('\\', "/");
+
+    // UNC path: \\server\share\rest
+    if path.starts_with("\\\\") {
+        let mut parts = normalized[2..].split('/').filter(|c| !c.is_empty());
+        let server = parts.next().unwrap_or_default().to_string();
+        let share = parts.next().unwrap_or_default().to_string();
+        return PathParts {
+            drive: None,
+            unc: Some((server, share)),
+            absolute: true,
+            components: parts.map(|c| c.to_string()).collect(),
+        };
+    }
+
+    let bytes = normalized.as_bytes();
+    let (drive, rest) = if bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' {
+        (Some((bytes[0] as char).to_ascii_uppercase()), &normalized[2..])
+    } else {
+        (None, normalized.as_str())
+    };
+
+    PathParts {
+        drive,
+        unc: None,
+        absolute: rest.starts_with('/'),
+        components: rest.split('/').filter(|c| !c.is_empty()).map(|c| c.to_string()).collect(),
+    }
+}
+
+fn format_unix_path(parts: &PathParts) -> String {
+    let joined = parts.components.join("/");
+    match &parts.unc {
+        Some((server, share)) => format!("//{}/{}/{}", server, share, joined).trim_end_matches('/').to_string(),
+        None if parts.absolute || parts.drive.is_some() => format!("/{}", joined),
+        None => joined,
+    }
+}
+
+fn format_windows_path(parts: &PathParts) -> String {
+    if let Some((server, share)) = &parts.unc {
+        return format!("\\\\{}\\{}\\{}", server, share, parts.components.join("\\"))
+            .trim_end_matches('\\')
+            .to_string();
+    }
+
+    // WSL mount paths (/mnt/c/...) map back to their drive letter
+    let mut drive = parts.drive;
+    let mut components: &[String] = &parts.components;
+    if drive.is_none() && parts.absolute && components.len() >= 2 && components[0] == "mnt" {
+        let letter = components[1].as_bytes();
+        if letter.len() == 1 && letter[0].is_ascii_alphabetic() {
+            drive = Some((letter[0] as char).to_ascii_uppercase());
+            components = &components[2..];
+        }
+    }
+
+    let joined = components.join("\\");
+    match drive {
+        Some(letter) => format!("{}:\\{}", letter, joined),
+        None if parts.absolute => format!("C:\\{}", joined),
+        None => joined,
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BreadcrumbSegment {
+    pub name: String,
+    pub full_path: String,
+}
+
+// The first segment is the drive ("C:\") or UNC share ("\\server\share") when there is one.
+// "/" on its own is a single root segment; otherwise Unix paths start at their first directory.
+#[tauri::command]
+fn get_path_breadcrumbs(path: String) -> Vec<BreadcrumbSegment> {
+    let parts = if cfg!(windows) || !path.starts_with('/') {
+        split_path_parts(&path)
+    } else {
+        // Backslashes are ordinary filename characters in Unix paths
+        PathParts {
+            drive: None,
+            unc: None,
+            absolute: true,
+            components: path.split('/').filter(|c| !c.is_empty()).map(|c| c.to_string()).collect(),
+        }
+    };
+
+    let mut segments = Vec::new();
+    let (mut prefix, separator) = match (&parts.unc, parts.drive) {
+        (Some((server, share)), _) => {
+            let share_path = format!("\\\\{}\\{}", server, share);
+            segments.push(BreadcrumbSegment { name: share_path.clone(), full_path: share_path.clone() });
+            (share_path, '\\')
+        }
+        (None, Some(letter)) => {
+            let drive_path = format!("{}:\\", letter);
+            segments.push(BreadcrumbSegment { name: drive_path.clone(), full_path: drive_path.clone() });
+            (drive_path, '\\')
+        }
+        (None, None) if parts.absolute && parts.components.is_empty() => {
+            return vec![BreadcrumbSegment { name: "/".to_string(), full_path: "/".to_string() }];
+        }
+        (None, None) if parts.absolute => (String::new(), '/'),
+        (None, None) => (String::new(), if cfg!(windows) { '\\' } else { '/' }),
+    };
+
+    for (i, component) in parts.components.iter().enumerate() {
+        // Drive roots already end in a separator, and relative paths start without one
+        if !prefix.ends_with(separator) && (i > 0 || parts.absolute) {
+            prefix.push(separator);
+        }
+        prefix.push_str(component);
+        segments.push(BreadcrumbSegment { name: component.clone(), full_path: prefix.clone() });
+    }
+    segments
+}
+
+// Percent-encode everything outside the RFC 3986 unreserved set
+fn percent_encode_component(component: &str) -> String {
+    let mut encoded = String::with_capacity(component.len());
+    for byte in component.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+fn format_file_uri(parts: &PathParts) -> Result<String, String> {
+    let encoded: Vec<String> = parts.components.iter().map(|c| percent_encode_component(c)).collect();
+    let joined = encoded.join("/");
+
+    if let Some((server, share)) = &parts.unc {
+        return Ok(format!("file://{}/{}/{}", server, percent_encode_component(share), joined)
+            .trim_end_matches('/')
+            .to_string());
+    }
+
+    match parts.drive {
+        Some(letter) => Ok(format!("file:///{}:/{}", letter, joined)),
+        None if parts.absolute => Ok(format!("file:///{}", joined)),
+        None => Err("File URIs require an absolute path".to_string()),
+    }
+}
+
+#[tauri::command]
+fn convert_path_format(path: String, to: String) -> Result<String, String> {
+    if path.is_empty() {
+        return Err("Path is empty".to_string());
+    }
+
+    let parts = split_path_parts(&path);
+
+    match to.as_str() {
+        "unix" => Ok(format_unix_path(&parts)),
+        "windows" => Ok(format_windows_path(&parts)),
+        "posix_wsl" => {
+            if parts.unc.is_some() {
+                return Err("UNC paths have no WSL equivalent".to_string());
+            }
+            match parts.drive {
+                Some(letter) => Ok(format!(
+                    "/mnt/{}/{}",
+                    letter.to_ascii_lowercase(),
+                    parts.components.join("/")
+                )
+                .trim_end_matches('/')
+                .to_string()),
+                None => Ok(format_unix_path(&parts)),
+            }
+        }
+        "file_uri" => format_file_uri(&parts),
+        "python_raw_string" => {
+            let windows = format_windows_path(&parts);
+            // Python raw strings cannot end in a backslash, so fall back to an escaped literal
+            if windows.ends_with('\\') {
+                Ok(format!("\"{}\"", windows.replace('\\', "\\\\")))
+            } else {
+                Ok(format!("r\"{}\"", windows))
+            }
+        }
+        "shell_quoted" => Ok(format!("'{}'", path.replace('\'', "'\\''"))),
+        _ => Err(format!(
+            "Unsupported path format: {} (expected unix, windows, posix_wsl, file_uri, python_raw_string or shell_quoted)",
+            to
+        )),
+    }
+}
+
+// Unlike convert_path_format's file_uri, this uses the host platform's path rules
+#[tauri::command]
+fn get_path_uri(path: String) -> Result<String, String> {
+    if path.contains('\0') {
+        return Err("Path contains a null byte".to_string());
+    }
+
+    url::Url::from_file_path(&path)
+        .map(|uri| uri.to_string())
+        .map_err(|_| format!("Path must be absolute to convert to a URI: {}", path))
+}
+
+#[tauri::command]
+fn get_path_from_uri(uri: String) -> Result<String, String> {
+    let parsed = url::Url::parse(&uri)
+        .map_err(|e| format!("Failed to parse URI: {}", e))?;
+    if parsed.scheme() != "file" {
+        return Err(format!("Not a file URI: {}", uri));
+    }
+
+    parsed.to_file_path()
+        .map(|p| p.to_string_lossy().to_string())
+        .map_err(|_| format!("URI does not map to a local path: {}", uri))
+}
+
+// Drop "." and fold "name/.." pairs, keeping leading ".." that can't be folded
+fn collapse_dot_components(components: &[String]) -> Vec<String> {
+    let mut collapsed: Vec<String> = Vec::new();
+    for component in components {
+        match component.as_str() {
+            "." => {}
+            ".." if collapsed.last().is_some_and(|c| c != "..") => {
+                collapsed.pop();
+            }
+            _ => collapsed.push(component.clone()),
+        }
+    }
+    collapsed
+}
+
+#[tauri::command]
+fn get_path_traversal_depth(from: String, to: String) -> Option<usize> {
+    if from.is_empty() || to.is_empty() {
+        return None;
+    }
+
+    let from_parts = split_path_parts(&from);
+    let to_parts = split_path_parts(&to);
+    if from_parts.drive != to_parts.drive || from_parts.unc != to_parts.unc {
+        return None;
+    }
+
+    // Drive-letter and UNC paths come from Windows, where names compare case-insensitively
+    let case_insensitive = from_parts.drive.is_some() || from_parts.unc.is_some();
+    let from_components = collapse_dot_components(&from_parts.components);
+    let to_components = collapse_dot_components(&to_parts.components);
+
+    let common = from_components.iter()
+        .zip(&to_components)
+        .take_while(|(a, b)| if case_insensitive { a.eq_ignore_ascii_case(b) } else { a == b })
+        .count();
+
+    Some((from_components.len() - common) + (to_components.len() - common))
+}
+
+#[tauri::command]
+fn get_paths_in_clipboard(app: tauri::AppHandle) -> Vec<String> {
+    use tauri_plugin_clipboard_manager::ClipboardExt;
+
+    let text = match app.clipboard().read_text() {
+        Ok(t) => t,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut seen = HashSet::new();
+    text.lines()
+        // Paths copied from shells and file managers are often quoted
+        .map(|line| line.trim().trim_matches(['"', '\'']))
+        .filter(|line| !line.is_empty())
+        .filter_map(|line| {
+            if line.starts_with("file://") {
+                get_path_from_uri(line.to_string()).ok()
+            } else {
+                Some(line.to_string())
+            }
+        })
+        .filter(|path| path_exists(path.clone()))
+        .filter(|path| seen.insert(path.clone()))
+        .collect()
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum PathFormat {
+    Native,
+    Unix,
+    Windows,
+    // Native separators, quoted for the host shell
+    Quoted,
+}
+
+fn native_path(path: &str) -> String {
+    if cfg!(windows) {
+        path.replace('/', "\\")
+    } else {
+        path.to_string()
+    }
+}
+
+fn format_path_for_clipboard(path: &str, format: PathFormat) -> String {
+    match format {
+        PathFormat::Native => native_path(path),
+        PathFormat::Unix => format_unix_path(&split_path_parts(path)),
+        PathFormat::Windows => format_windows_path(&split_path_parts(path)),
+        // Windows paths cannot contain '"', so plain double quotes are always safe there
+        PathFormat::Quoted if cfg!(windows) => format!("\"{}\"", native_path(path)),
+        PathFormat::Quoted => format!("'{}'", path.replace('\'', "'\\''")),
+    }
+}
+
+#[tauri::command]
+fn copy_path_to_clipboard(app: tauri::AppHandle, path: String, format: Option<PathFormat>) -> Result<(), String> {
+    use tauri_plugin_clipboard_manager::ClipboardExt;
+
+    if path.is_empty() {
+        return Err("Path is empty".to_string());
+    }
+    let text = format_path_for_clipboard(&path, format.unwrap_or(PathFormat::Native));
+    app.clipboard()
+        .write_text(text)
+        .map_err(|e| format!("Failed to write to clipboard: {}", e))
+}
+
+// Resolve . and .. components without touching the filesystem
+fn normalize_lexically(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => match normalized.components().next_back() {
+                Some(Component::Normal(_)) => {
+                    normalized.pop();
+                }
+                // Can't go above the root
+                Some(Component::RootDir) | Some(Component::Prefix(_)) => {}
+                _ => normalized.push(".."),
+            },
+            other => normalized.push(other.as_os_str()),
+        }
+    }
+    normalized
+}
+
+// Rebuilding from components also drops trailing and repeated separators and, on Windows,
+// rewrites '/' as '\' while keeping the UNC prefix
+#[tauri::command]
+fn get_path_normalized(path: String) -> String {
+    if path.is_empty() {
+        return path;
+    }
+
+    let normalized = normalize_lexically(Path::new(&path));
+    if normalized.as_os_str().is_empty() {
+        // "a/.." and "." both point at the current directory
+        return ".".to_string();
+    }
+    normalized.to_string_lossy().to_string()
+}
+
+// Canonicalize the longest existing ancestor and re-append the rest, so paths
+// that don't exist yet (e.g. copy destinations) still get symlinks resolved
+fn canonicalize_allow_missing(path: &Path) -> Result<PathBuf, String> {
+    let normalized = normalize_lexically(path);
+    let mut existing = normalized.as_path();
+    let mut missing = Vec::new();
+
+    while !existing.exists() {
+        let name = existing.file_name()
+            .ok_or_else(|| format!("Path has no existing ancestor: {}", path.display()))?;
+        missing.push(name.to_os_string());
+        existing = existing.parent()
+            .ok_or_else(|| format!("Path has no existing ancestor: {}", path.display()))?;
+    }
+
+    let mut resolved = fs::canonicalize(existing)
+        .map_err(|e| format!("Failed to resolve path: {}", e))?;
+    for name in missing.iter().rev() {
+        resolved.push(name);
+    }
+    Ok(resolved)
+}
+
+// Join an untrusted relative path onto base, refusing anything that escapes it.
+// Also validates the paths copy_entry, move_entry and delete_entry act on.
+fn resolve_within_base(base: &Path, untrusted: &Path) -> Result<PathBuf, String> {
+    let canonical_base = fs::canonicalize(base)
+        .map_err(|e| format!("Failed to resolve base path: {}", e))?;
+    let resolved = canonicalize_allow_missing(&canonical_base.join(untrusted))?;
+
+    if !resolved.starts_with(&canonical_base) {
+        return Err(format!("Path escapes base directory: {}", untrusted.display()));
+    }
+    Ok(resolved)
+}
+
+#[tauri::command]
+fn get_path_traversal_safe(base: String, untrusted: String) -> Result<String, String> {
+    resolve_within_base(Path::new(&base), Path::new(&untrusted))
+        .map(|p| p.to_string_lossy().to_string())
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TreeNode {
+    pub name: String,
+    pub path: String,
+    pub is_directory: bool,
+    pub children: Vec<TreeNode>,
+}
+
+const MAX_TREE_NODES: usize = 20_000;
+
+struct TreeBuildState<'a> {
+    app: &'a tauri::AppHandle,
+    seen: HashSet<PathBuf>,
+    node_count: usize,
+    max_depth: u32,
+}
+
+fn build_tree_node(path: &Path, is_dir: bool, depth: u32, build: &mut TreeBuildState) -> Option<TreeNode> {
+    // Overlapping roots would otherwise list the same subtree twice
+    if build.node_count >= MAX_TREE_NODES || !build.seen.insert(path.to_path_buf()) {
+        return None;
+    }
+
+    build.node_count += 1;
+    if build.node_count.is_multiple_of(1000) {
+        let _ = build.app.emit("tree-build-progress", build.node_count);
+    }
+
+    let mut node = TreeNode {
+        name: path.file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.to_string_lossy().to_string()),
+        path: path.to_string_lossy().to_string(),
+        is_directory: is_dir,
+        children: Vec::new(),
+    };
+
+    if !is_dir || depth >= build.max_depth {
+        return Some(node);
+    }
+
+    let mut children: Vec<(PathBuf, bool)> = match fs::read_dir(path) {
+        Ok(read_dir) => read_dir
+            .flatten()
+            .filter(|e| !e.file_name().to_string_lossy().starts_with('.'))
+            .map(|e| {
+                let child_path = e.path();
+                let child_is_dir = child_path.is_dir();
+                (child_path, child_is_dir)
+            })
+            .collect(),
+        Err(_) => return Some(node),
+    };
+    children.sort_by(|a, b| {
+        dirs_first_order(a.1, &a.0.to_string_lossy(), b.1, &b.0.to_string_lossy())
+    });
+
+    for (child_path, child_is_dir) in children {
+        if let Some(child) = build_tree_node(&child_path, child_is_dir, depth + 1, build) {
+            node.children.push(child);
+        }
+    }
+
+    Some(node)
+}
+
+#[tauri::command]
+fn list_directory_trees(app: tauri::AppHandle, roots: Vec<String>, max_depth: u32) -> Vec<TreeNode> {
+    let mut build = TreeBuildState {
+        app: &app,
+        seen: HashSet::new(),
+        node_count: 0,
+        max_depth,
+    };
+
+    roots.iter()
+        .map(PathBuf::from)
+        .filter(|root| root.is_dir())
+        .filter_map(|root| build_tree_node(&root, true, 0, &mut build))
+        .collect()
+}
+
+// Same heuristic as git: a null byte in the first 8 KB means binary
+fn looks_binary(bytes: &[u8]) -> bool {
+    bytes.iter().take(8 * 1024).any(|&b| b == 0)
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BinaryCheck {
+    pub is_binary: bool,
+    // 0.0 is probably text, 1.0 definitely binary
+    pub confidence: f32,
+}
+
+// Control characters other than tab, newline, carriage return and form feed
+fn non_printable_ratio(bytes: &[u8]) -> f32 {
+    if bytes.is_empty() {
+        return 0.0;
+    }
+    let non_printable = bytes.iter()
+        .filter(|&&b| (b < 0x20 && !matches!(b, b'\t' | b'\n' | b'\r' | 0x0C)) || b == 0x7F)
+        .count();
+    non_printable as f32 / bytes.len() as f32
+}
+
+#[tauri::command]
+fn get_file_is_binary(path: String) -> Result<BinaryCheck, String> {
+    use std::io::Read;
+
+    let file = fs::File::open(&path)
+        .map_err(|e| format!("Failed to open file: {}", e))?;
+    let mut head = Vec::with_capacity(8 * 1024);
+    file.take(8 * 1024)
+        .read_to_end(&mut head)
+        .map_err(|e| format!("Failed to read file: {}", e))?;
+
+    if looks_binary(&head) {
+        return Ok(BinaryCheck { is_binary: true, confidence: 1.0 });
+    }
+    // Text rarely has more than a few percent control characters; 30% is treated as certain
+    Ok(BinaryCheck {
+        is_binary: false,
+        confidence: (non_printable_ratio(&head) / 0.3).min(1.0),
+    })
+}
+
+fn hash_file_with<D: sha2::Digest>(path: &Path) -> Result<String, String> {
+    use std::io::Read;
+
+    let mut file = fs::File::open(path).map_err(|e| format!("Failed to open file: {}", e))?;
+    let mut hasher = D::new();
+    let mut buf = vec![0u8; 64 * 1024];
+    loop {
+        let read = file.read(&mut buf).map_err(|e| format!("Failed to read file: {}", e))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buf[..read]);
+    }
+
+    Ok(hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+// Lowercase hex digest of a file, streamed so large files aren't held in memory
+fn compute_file_hash(path: &Path, algorithm: &str) -> Result<String, String> {
+    match algorithm.to_lowercase().as_str() {
+        "sha256" => hash_file_with::<sha2::Sha256>(path),
+        "sha512" => hash_file_with::<sha2::Sha512>(path),
+        "md5" => hash_file_with::<md5::Md5>(path),
+        _ => Err(format!("Unsupported hash algorithm: {} (expected sha256, sha512 or md5)", algorithm)),
+    }
+}
+
+// Hashing is disk-bound; more threads than this just thrash the drive
+const HASH_THREADS: usize = 4;
+
+// Built on first use and shared by every batch
+static HASH_POOL: OnceLock<Result<rayon::ThreadPool, String>> = OnceLock::new();
+
+// Hashes on the shared pool; results come back in the order of `paths`
+#[tauri::command]
+async fn get_file_checksum_batch(app: tauri::AppHandle, paths: Vec<String>, algorithm: String) -> Vec<Result<String, String>> {
+    use rayon::prelude::*;
+
+    const MAX_BATCH_FILES: usize = 100;
+    const MAX_BATCH_BYTES: u64 = 10 * 1024 * 1024 * 1024;
+
+    let count = paths.len();
+    let hash_batch = move || {
+        let reject = |reason: String| paths.iter().map(|_| Err(reason.clone())).collect();
+
+        if paths.len() > MAX_BATCH_FILES {
+            return reject(format!("Batch has {} files (max {})", paths.len(), MAX_BATCH_FILES));
+        }
+        let total_bytes: u64 = paths.iter()
+            .filter_map(|p| fs::metadata(p).ok())
+            .map(|m| m.len())
+            .sum();
+        if total_bytes > MAX_BATCH_BYTES {
+            return reject(format!("Batch totals {} bytes (max {})", total_bytes, MAX_BATCH_BYTES));
+        }
+
+        let pool = HASH_POOL.get_or_init(|| {
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(HASH_THREADS)
+                .build()
+                .map_err(|e| e.to_string())
+        });
+        let pool = match pool {
+            Ok(pool) => pool,
+            Err(e) => return reject(format!("Failed to start hashing threads: {}", e)),
+        };
+
+        let completed = AtomicUsize::new(0);
+        let total = paths.len();
+        pool.install(|| {
+            // par_iter + collect keeps results in input order
+            paths.par_iter()
+                .map(|path| {
+                    let result = compute_file_hash(Path::new(path), &algorithm);
+                    let done = completed.fetch_add(1, Ordering::Relaxed) + 1;
+                    if done.is_multiple_of(5) || done == total {
+                        let _ = app.emit("hash-batch-progress", serde_json::json!({
+                            "completed": done,
+                            "total": total,
+                        }));
+                    }
+                    result
+                })
+                .collect()
+        })
+    };
+
+    match tauri::async_runtime::spawn_blocking(hash_batch).await {
+        Ok(results) => results,
+        Err(e) => (0..count).map(|_| Err(format!("Checksum batch failed: {}", e))).collect(),
+    }
+}
+
+const LARGE_FILE_BYTES: u64 = 50 * 1024 * 1024;
+const LINE_CHECKPOINT_INTERVAL: usize = 1000;
+const MAX_LINE_OFFSET_CACHE: usize = 16;
+
+// Byte offset of every LINE_CHECKPOINT_INTERVAL-th line, keyed by (path, mtime).
+// Sparse so even huge files only need a small index.
+type LineOffsetCache = HashMap<(String, u64), Arc<Vec<u64>>>;
+
+static LINE_OFFSET_CACHE: OnceLock<Mutex<LineOffsetCache>> = OnceLock::new();
+
+fn build_line_checkpoints(path: &Path) -> Result<Vec<u64>, String> {
+    use std::io::BufRead;
+
+    let file = fs::File::open(path).map_err(|e| format!("Failed to open file: {}", e))?;
+    let mut reader = std::io::BufReader::new(file);
+    let mut checkpoints = vec![0];
+    let mut offset = 0u64;
+    let mut line = 0usize;
+    let mut buf = Vec::new();
+
+    loop {
+        buf.clear();
+        let read = reader.read_until(b'\n', &mut buf)
+            .map_err(|e| format!("Failed to read file: {}", e))?;
+        if read == 0 {
+            break;
+        }
+        offset += read as u64;
+        line += 1;
+        if line.is_multiple_of(LINE_CHECKPOINT_INTERVAL) {
+            checkpoints.push(offset);
+        }
+    }
+    Ok(checkpoints)
+}
+
+fn line_checkpoints(path: &Path, modified: u64) -> Result<Arc<Vec<u64>>, String> {
+    let cache = LINE_OFFSET_CACHE.get_or_init(|| Mutex::new(HashMap::new()));
+    let key = (path.to_string_lossy().to_string(), modified);
+    if let Some(checkpoints) = cache.lock().map_err(|e| e.to_string())?.get(&key) {
+        return Ok(Arc::clone(checkpoints));
+    }
+
+    let checkpoints = Arc::new(build_line_checkpoints(path)?);
+    let mut cache = cache.lock().map_err(|e| e.to_string())?;
+    if cache.len() >= MAX_LINE_OFFSET_CACHE {
+        cache.clear();
+    }
+    cache.insert(key, Arc::clone(&checkpoints));
+    Ok(checkpoints)
+}
+
+// Lines are 1-indexed and inclusive
+#[tauri::command]
+fn get_file_preview_lines(path: String, start_line: usize, end_line: usize) -> Result<Vec<String>, String> {
+    use std::io::{BufRead, Seek, SeekFrom};
+
+    const MAX_LINES: usize = 1000;
+
+    if start_line == 0 || end_line < start_line {
+        return Err("Line range must start at 1 and end at or after its start".to_string());
+    }
+    let end_line = end_line.min(start_line.saturating_add(MAX_LINES - 1));
+
+    let target = PathBuf::from(&path);
+    let metadata = fs::metadata(&target)
+        .map_err(|e| format!("Failed to read metadata: {}", e))?;
+    if !metadata.is_file() {
+        return Err(format!("Not a file: {}", path));
+    }
+
+    let file = fs::File::open(&target).map_err(|e| format!("Failed to open file: {}", e))?;
+    let mut reader = std::io::BufReader::new(file);
+    let mut line_number = 1;
+
+    // Large files jump to the nearest checkpoint instead of scanning from the start
+    if metadata.len() > LARGE_FILE_BYTES {
+        let modified = metadata.modified().ok().and_then(system_time_secs).unwrap_or(0);
+        let checkpoints = line_checkpoints(&target, modified)?;
+        let index = ((start_line - 1) / LINE_CHECKPOINT_INTERVAL).min(checkpoints.len() - 1);
+        reader.seek(SeekFrom::Start(checkpoints[index]))
+            .map_err(|e| format!("Failed to seek: {}", e))?;
+        line_number = index * LINE_CHECKPOINT_INTERVAL + 1;
+    }
+
+    let mut lines = Vec::new();
+    let mut buf = Vec::new();
+    while line_number <= end_line {
+        buf.clear();
+        let read = reader.read_until(b'\n', &mut buf)
+            .map_err(|e| format!("Failed to read file: {}", e))?;
+        if read == 0 {
+            break;
+        }
+        if line_number >= start_line {
+            let text = String::from_utf8_lossy(&buf);
+            lines.push(text.trim_end_matches(['\r', '\n']).to_string());
+        }
+        line_number += 1;
+    }
+
+    Ok(lines)
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DiffHunk {
+    pub old_start: usize,
+    pub old_lines: Vec<String>,
+    pub new_start: usize,
+    pub new_lines: Vec<String>,
+    pub context: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FileDiff {
+    pub hunks: Vec<DiffHunk>,
+    pub insertions: usize,
+    pub deletions: usize,
+}
+
+const MAX_DIFF_FILE_BYTES: u64 = 2 * 1024 * 1024;
+
+fn read_text_for_diff(path: &str) -> Result<String, String> {
+    let metadata = fs::metadata(path)
+        .map_err(|e| format!("Failed to read metadata for {}: {}", path, e))?;
+    if metadata.len() > MAX_DIFF_FILE_BYTES {
+        return Err(format!("File is too large to diff (max 2 MB): {}", path));
+    }
+
+    let bytes = fs::read(path)
+        .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    if looks_binary(&bytes) {
+        return Err(format!("Cannot diff binary file: {}", path));
+    }
+    Ok(String::from_utf8_lossy(&bytes).to_string())
+}
+
+#[tauri::command]
+fn get_file_diff(path_a: String, path_b: String, context_lines: Option<usize>) -> Result<FileDiff, String> {
+    let old_text = read_text_for_diff(&path_a)?;
+    let new_text = read_text_for_diff(&path_b)?;
+    let diff = similar::TextDiff::from_lines(&old_text, &new_text);
+
+    let mut hunks = Vec::new();
+    let mut insertions = 0;
+    let mut deletions = 0;
+
+    for group in diff.grouped_ops(context_lines.unwrap_or(3)) {
+        let (old_start, new_start) = match group.first() {
+            Some(op) => (op.old_range().start + 1, op.new_range().start + 1),
+            None => continue,
+        };
+        let mut hunk = DiffHunk {
+            old_start,
+            old_lines: Vec::new(),
+            new_start,
+            new_lines: Vec::new(),
+            context: Vec::new(),
+        };
+
+        for op in &group {
+            for change in diff.iter_changes(op) {
+                let line = change.value().trim_end_matches(['\r', '\n']).to_string();
+                match change.tag() {
+                    similar::ChangeTag::Delete => {
+                        deletions += 1;
+                        hunk.old_lines.push(line);
+                    }
+                    similar::ChangeTag::Insert => {
+                        insertions += 1;
+                        hunk.new_lines.push(line);
+                    }
+                    similar::ChangeTag::Equal => hunk.context.push(line),
+                }
+            }
+        }
+        hunks.push(hunk);
+    }
+
+    Ok(FileDiff { hunks, insertions, deletions })
+}
+
+fn atomic_temp_path(dest: &Path) -> Option<PathBuf> {
+    let file_name = dest.file_name()?;
+    Some(dest.with_file_name(format!(".{}.spyglass-tmp", file_name.to_string_lossy())))
+}
+
+// Write and fsync a sibling temp file, then rename it over the destination. Readers never
+// see a partial file, and a crash can't leave the destination empty or truncated.
+fn write_file_atomic(dest: &Path, bytes: &[u8]) -> Result<(), String> {
+    use std::io::Write;
+
+    let temp = atomic_temp_path(dest)
+        .ok_or_else(|| format!("Invalid destination: {}", dest.display()))?;
+
+    let write_temp = || -> std::io::Result<()> {
+        let mut file = fs::File::create(&temp)?;
+        file.write_all(bytes)?;
+        file.sync_all()
+    };
+    write_temp().map_err(|e| {
+        let _ = fs::remove_file(&temp);
+        format!("Failed to write temporary file: {}", e)
+    })?;
+    fs::rename(&temp, dest).map_err(|e| {
+        let _ = fs::remove_file(&temp);
+        format!("Failed to replace destination: {}", e)
+    })
+}
+
+// A BOM wins; otherwise valid UTF-8 is UTF-8 and anything else is treated as Latin-1
+fn detect_text_encoding(bytes: &[u8]) -> (&'static encoding_rs::Encoding, usize) {
+    if let Some((encoding, bom_len)) = encoding_rs::Encoding::for_bom(bytes) {
+        return (encoding, bom_len);
+    }
+    if std::str::from_utf8(bytes).is_ok() {
+        (encoding_rs::UTF_8, 0)
+    } else {
+        (encoding_rs::WINDOWS_1252, 0)
+    }
+}
+
+fn encode_text(text: &str, target_encoding: &str, add_bom: bool) -> Result<Vec<u8>, String> {
+    let mut out = Vec::with_capacity(text.len() + 3);
+    match target_encoding.to_ascii_uppercase().as_str() {
+        "UTF-8" => {
+            if add_bom {
+                out.extend_from_slice(&[0xEF, 0xBB, 0xBF]);
+            }
+            out.extend_from_slice(text.as_bytes());
+        }
+        // encoding_rs only decodes UTF-16, so the encoding side is done by hand
+        "UTF-16LE" => {
+            if add_bom {
+                out.extend_from_slice(&[0xFF, 0xFE]);
+            }
+            out.extend(text.encode_utf16().flat_map(|unit| unit.to_le_bytes()));
+        }
+        "UTF-16BE" => {
+            if add_bom {
+                out.extend_from_slice(&[0xFE, 0xFF]);
+            }
+            out.extend(text.encode_utf16().flat_map(|unit| unit.to_be_bytes()));
+        }
+        // True ISO-8859-1 maps code points 0-255 directly; encoding_rs would give windows-1252
+        "ISO-8859-1" => {
+            if add_bom {
+                return Err("ISO-8859-1 has no byte order mark".to_string());
+            }
+            for c in text.chars() {
+                let code = c as u32;
+                if code > 0xFF {
+                    return Err(format!("Character '{}' cannot be represented in ISO-8859-1", c));
+                }
+                out.push(code as u8);
+            }
+        }
+        _ => {
+            return Err(format!(
+                "Unsupported encoding: {} (expected UTF-8, UTF-16LE, UTF-16BE or ISO-8859-1)",
+                target_encoding
+            ))
+        }
+    }
+    Ok(out)
+}
+
+#[tauri::command]
+fn detect_encoding_and_transcode(src: String, target_encoding: String, dest: String, add_bom: bool) -> Result<(), String> {
+    const MAX_TRANSCODE_BYTES: u64 = 50 * 1024 * 1024;
+
+    let metadata = fs::metadata(&src)
+        .map_err(|e| format!("Failed to read metadata: {}", e))?;
+    if metadata.len() > MAX_TRANSCODE_BYTES {
+        return Err(format!("File is too large to transcode: {} bytes", metadata.len()));
+    }
+
+    let bytes = fs::read(&src).map_err(|e| format!("Failed to read file: {}", e))?;
+    let (encoding, bom_len) = detect_text_encoding(&bytes);
+    // UTF-16 text is full of null bytes, so only BOM-less input goes through the binary check
+    if bom_len == 0 && looks_binary(&bytes) {
+        return Err("File appears to be binary".to_string());
+    }
+
+    let (text, had_errors) = encoding.decode_without_bom_handling(&bytes[bom_len..]);
+    if had_errors {
+        return Err(format!("File is not valid {}", encoding.name()));
+    }
+
+    let encoded = encode_text(&text, &target_encoding, add_bom)?;
+    write_file_atomic(Path::new(&dest), &encoded)
+}
+
+#[cfg(target_os = "macos")]
+fn statfs_for(path: &Path) -> Result<libc::statfs, String> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = std::ffi::CString::new(path.as_os_str().as_bytes())
+        .map_err(|_| "Path contains a null byte".to_string())?;
+    let mut stat: libc::statfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return Err(format!("statfs failed: {}", std::io::Error::last_os_error()));
+    }
+    Ok(stat)
+}
+
+#[cfg(target_os = "macos")]
+fn c_chars_to_string(chars: &[libc::c_char]) -> String {
+    let bytes: Vec<u8> = chars.iter().take_while(|&&c| c != 0).map(|&c| c as u8).collect();
+    String::from_utf8_lossy(&bytes).to_string()
+}
+
+#[cfg(windows)]
+fn windows_volume_path(path: &Path) -> Result<String, String> {
+    use windows_sys::Win32::Storage::FileSystem::GetVolumePathNameW;
+
+    let wide = to_wide_null(path.as_os_str());
+    let mut buf = vec![0u16; 1024];
+    if unsafe { GetVolumePathNameW(wide.as_ptr(), buf.as_mut_ptr(), buf.len() as u32) } == 0 {
+        return Err(format!("GetVolumePathName failed: {}", std::io::Error::last_os_error()));
+    }
+    Ok(from_wide_null(&buf))
+}
+
+// On Linux, walk upward until the device id changes
+#[cfg(target_os = "linux")]
+fn mount_point_for(path: &Path) -> Result<PathBuf, String> {
+    use std::os::unix::fs::MetadataExt;
+
+    let canonical = fs::canonicalize(path)
+        .map_err(|e| format!("Failed to resolve path: {}", e))?;
+    let dev = fs::metadata(&canonical)
+        .map_err(|e| format!("Failed to read metadata: {}", e))?
+        .dev();
+
+    let mut current = canonical.as_path();
+    while let Some(parent) = current.parent() {
+        match fs::metadata(parent) {
+            Ok(m) if m.dev() == dev => current = parent,
+            _ => break,
+        }
+    }
+    Ok(current.to_path_buf())
+}
+
+#[cfg(target_os = "macos")]
+fn mount_point_for(path: &Path) -> Result<PathBuf, String> {
+    statfs_for(path).map(|stat| PathBuf::from(c_chars_to_string(&stat.f_mntonname)))
+}
+
+#[cfg(windows)]
+fn mount_point_for(path: &Path) -> Result<PathBuf, String> {
+    windows_volume_path(path).map(PathBuf::from)
+}
+
+#[tauri::command]
+fn get_mount_point(path: String) -> Result<String, String> {
+    let path = PathBuf::from(&path);
+    if !path.exists() {
+        return Err(format!("Path does not exist: {}", path.display()));
+    }
+    mount_point_for(&path).map(|p| p.to_string_lossy().to_string())
+}
+
+#[cfg(target_os = "linux")]
+fn filesystem_type_for(path: &Path) -> Result<String, String> {
+    find_proc_mount(path)
+        .map(|m| m.fs_type)
+        .ok_or_else(|| format!("No mount found for: {}", path.display()))
+}
+
+#[cfg(target_os = "macos")]
+fn filesystem_type_for(path: &Path) -> Result<String, String> {
+    statfs_for(path).map(|stat| c_chars_to_string(&stat.f_fstypename))
+}
+
+#[cfg(windows)]
+fn filesystem_type_for(path: &Path) -> Result<String, String> {
+    windows_volume_info(&windows_volume_path(path)?).map(|(fs_name, _)| fs_name)
+}
+
+// Filesystem name (lowercased) and flags for a volume root like "C:\\"
+#[cfg(windows)]
+fn windows_volume_info(volume_root: &str) -> Result<(String, u32), String> {
+    use windows_sys::Win32::Storage::FileSystem::GetVolumeInformationW;
+
+    let volume = to_wide_null(std::ffi::OsStr::new(volume_root));
+    let mut fs_name = vec![0u16; 64];
+    let mut flags = 0u32;
+    let ok = unsafe {
+        GetVolumeInformationW(
+            volume.as_ptr(),
+            std::ptr::null_mut(),
+            0,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            &mut flags,
+            fs_name.as_mut_ptr(),
+            fs_name.len() as u32,
+        )
+    };
+    if ok == 0 {
+        return Err(format!("GetVolumeInformation failed: {}", std::io::Error::last_os_error()));
+    }
+    Ok((from_wide_null(&fs_name).to_lowercase(), flags))
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MountPoint {
+    pub device: String,
+    pub mount_path: String,
+    pub fs_type: String,
+    pub total_bytes: u64,
+    pub free_bytes: u64,
+    pub is_readonly: bool,
+    pub is_remote: bool,
+}
+
+#[cfg(target_os = "linux")]
+fn list_mount_points() -> Vec<MountPoint> {
+    const PSEUDO_FILESYSTEMS: [&str; 20] = [
+        "proc", "sysfs", "devtmpfs", "devpts", "cgroup", "cgroup2", "securityfs", "pstore",
+        "debugfs", "tracefs", "configfs", "fusectl", "mqueue", "hugetlbfs", "bpf", "autofs",
+        "binfmt_misc", "rpc_pipefs", "nsfs", "efivarfs",
+    ];
+    const REMOTE_FILESYSTEMS: [&str; 8] = ["nfs", "nfs4", "cifs", "smb3", "smbfs", "afs", "9p", "fuse.sshfs"];
+
+    read_proc_mounts()
+        .into_iter()
+        .filter(|m| !PSEUDO_FILESYSTEMS.contains(&m.fs_type.as_str()))
+        .map(|m| {
+            let (total_bytes, free_bytes) = statvfs_space(&m.mount_path).unwrap_or((0, 0));
+            MountPoint {
+                is_readonly: m.options.iter().any(|o| o == "ro"),
+                is_remote: REMOTE_FILESYSTEMS.contains(&m.fs_type.as_str()),
+                device: m.device,
+                mount_path: m.mount_path,
+                fs_type: m.fs_type,
+                total_bytes,
+                free_bytes,
+            }
+        })
+        .collect()
+}
+
+// Total and available bytes for the filesystem containing `path`
+#[cfg(target_os = "linux")]
+fn statvfs_space(path: &str) -> Option<(u64, u64)> {
+    let c_path = std::ffi::CString::new(path).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+    let block_size = stat.f_frsize as u64;
+    Some((stat.f_blocks as u64 * block_size, stat.f_bavail as u64 * block_size))
+}
+
+#[cfg(target_os = "macos")]
+fn list_mount_points() -> Vec<MountPoint> {
+    let mut mounts: *mut libc::statfs = std::ptr::null_mut();
+    let count = unsafe { libc::getmntinfo(&mut mounts, libc::MNT_NOWAIT) };
+    if count <= 0 || mounts.is_null() {
+        return Vec::new();
+    }
+
+    // getmntinfo owns the buffer; it stays valid until the next call
+    let mounts = unsafe { std::slice::from_raw_parts(mounts, count as usize) };
+    mounts.iter()
+        .filter(|m| {
+            let fs_type = c_chars_to_string(&m.f_fstypename);
+            fs_type != "devfs" && fs_type != "autofs"
+        })
+        .map(|m| {
+            let block_size = m.f_bsize as u64;
+            MountPoint {
+                device: c_chars_to_string(&m.f_mntfromname),
+                mount_path: c_chars_to_string(&m.f_mntonname),
+                fs_type: c_chars_to_string(&m.f_fstypename),
+                total_bytes: m.f_blocks * block_size,
+                free_bytes: m.f_bavail * block_size,
+                is_readonly: m.f_flags & libc::MNT_RDONLY as u32 != 0,
+                is_remote: m.f_flags & libc::MNT_LOCAL as u32 == 0,
+            }
+        })
+        .collect()
+}
+
+#[cfg(windows)]
+fn list_mount_points() -> Vec<MountPoint> {
+    use windows_sys::Win32::Storage::FileSystem::{GetDiskFreeSpaceExW, GetDriveTypeW, GetLogicalDrives};
+
+    const DRIVE_REMOTE: u32 = 4;
+    const FILE_READ_ONLY_VOLUME: u32 = 0x0008_0000;
+
+    let drive_mask = unsafe { GetLogicalDrives() };
+    (0..26u8)
+        .filter(|i| drive_mask & (1 << i) != 0)
+        .filter_map(|i| {
+            let root = format!("{}:\\", (b'A' + i) as char);
+            let wide_root = to_wide_null(std::ffi::OsStr::new(&root));
+            // Empty card readers and optical drives have no volume information
+            let (fs_type, flags) = windows_volume_info(&root).ok()?;
+
+            let mut free_bytes = 0u64;
+            let mut total_bytes = 0u64;
+            unsafe {
+                GetDiskFreeSpaceExW(wide_root.as_ptr(), &mut free_bytes, &mut total_bytes, std::ptr::null_mut());
+            }
+            let drive_type = unsafe { GetDriveTypeW(wide_root.as_ptr()) };
+
+            Some(MountPoint {
+                device: root.trim_end_matches('\\').to_string(),
+                mount_path: root,
+                fs_type,
+                total_bytes,
+                free_bytes,
+                is_readonly: flags & FILE_READ_ONLY_VOLUME != 0,
+                is_remote: drive_type == DRIVE_REMOTE,
+            })
+        })
+        .collect()
+}
+
+#[tauri::command]
+fn get_mount_points() -> Vec<MountPoint> {
+    list_mount_points()
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum DriveType {
+    Fixed,
+    Removable,
+    Network,
+    CdRom,
+    Unknown,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DriveInfo {
+    pub path: String,
+    pub name: String,
+    pub total_bytes: Option<u64>,
+    pub free_bytes: Option<u64>,
+    pub drive_type: DriveType,
+}
+
+#[cfg(not(windows))]
+const OPTICAL_FILESYSTEMS: [&str; 3] = ["iso9660", "udf", "cd9660"];
+
+// A partition is removable when the disk it belongs to is
+#[cfg(target_os = "linux")]
+fn linux_device_is_removable(device: &str) -> bool {
+    // Resolves /dev/disk/by-uuid/... style links to the real node
+    let device = fs::canonicalize(device).unwrap_or_else(|_| PathBuf::from(device));
+    let sys_block = match device.file_name()
+        .and_then(|name| fs::canonicalize(Path::new("/sys/class/block").join(name)).ok())
+    {
+        Some(p) => p,
+        None => return false,
+    };
+    let disk = if sys_block.join("partition").exists() {
+        sys_block.parent().map(Path::to_path_buf).unwrap_or(sys_block)
+    } else {
+        sys_block
+    };
+    fs::read_to_string(disk.join("removable")).is_ok_and(|r| r.trim() == "1")
+}
+
+// Block devices and network shares; loop devices (snaps, disk images) and bind mounts of
+// an already listed device are left out
+#[cfg(target_os = "linux")]
+fn list_drives() -> Vec<DriveInfo> {
+    let mut seen_devices = HashSet::new();
+    list_mount_points().into_iter()
+        .filter(|m| m.is_remote || (m.device.starts_with("/dev/") && !m.device.starts_with("/dev/loop")))
+        .filter(|m| seen_devices.insert(m.device.clone()))
+        .map(|m| {
+            let drive_type = if m.is_remote {
+                DriveType::Network
+            } else if OPTICAL_FILESYSTEMS.contains(&m.fs_type.as_str()) {
+                DriveType::CdRom
+            } else if linux_device_is_removable(&m.device) {
+                DriveType::Removable
+            } else {
+                DriveType::Fixed
+            };
+            let space = statvfs_space(&m.mount_path);
+            DriveInfo {
+                name: Path::new(&m.mount_path).file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_else(|| m.mount_path.clone()),
+                path: m.mount_path,
+                total_bytes: space.map(|(total, _)| total),
+                free_bytes: space.map(|(_, free)| free),
+                drive_type,
+            }
+        })
+        .collect()
+}
+
+// The startup disk plus everything in /Volumes; the /Volumes entry that links back to "/"
+// only lends the startup disk its name
+#[cfg(target_os = "macos")]
+fn list_drives() -> Vec<DriveInfo> {
+    let mounts = list_mount_points();
+    let mut root_name = "/".to_string();
+    let mut volumes = Vec::new();
+
+    if let Ok(read_dir) = fs::read_dir("/Volumes") {
+        for entry in read_dir.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if fs::canonicalize(entry.path()).is_ok_and(|p| p == Path::new("/")) {
+                root_name = name;
+            } else {
+                volumes.push((entry.path().to_string_lossy().to_string(), name));
+            }
+        }
+    }
+    volumes.sort();
+    volumes.insert(0, ("/".to_string(), root_name));
+
+    volumes.into_iter()
+        .map(|(path, name)| {
+            let mount = mounts.iter().find(|m| m.mount_path == path);
+            let drive_type = match mount {
+                None => DriveType::Unknown,
+                Some(m) if m.is_remote => DriveType::Network,
+                Some(m) if OPTICAL_FILESYSTEMS.contains(&m.fs_type.as_str()) => DriveType::CdRom,
+                Some(_) if path == "/" => DriveType::Fixed,
+                Some(_) => DriveType::Removable,
+            };
+            DriveInfo {
+                total_bytes: mount.map(|m| m.total_bytes),
+                free_bytes: mount.map(|m| m.free_bytes),
+                path,
+                name,
+                drive_type,
+            }
+        })
+        .collect()
+}
+
+// Unlike list_mount_points, empty card readers and optical drives are still listed
+#[cfg(windows)]
+fn list_drives() -> Vec<DriveInfo> {
+    use windows_sys::Win32::Storage::FileSystem::{GetDiskFreeSpaceExW, GetDriveTypeW, GetLogicalDrives};
+
+    let drive_mask = unsafe { GetLogicalDrives() };
+    (0..26u8)
+        .filter(|i| drive_mask & (1 << i) != 0)
+        .map(|i| {
+            let root = format!("{}:\\", (b'A' + i) as char);
+            let wide_root = to_wide_null(std::ffi::OsStr::new(&root));
+
+            let mut free_bytes = 0u64;
+            let mut total_bytes = 0u64;
+            let has_space = unsafe {
+                GetDiskFreeSpaceExW(wide_root.as_ptr(), &mut free_bytes, &mut total_bytes, std::ptr::null_mut())
+            } != 0;
+            let drive_type = match unsafe { GetDriveTypeW(wide_root.as_ptr()) } {
+                2 => DriveType::Removable,
+                3 => DriveType::Fixed,
+                4 => DriveType::Network,
+                5 => DriveType::CdRom,
+                _ => DriveType::Unknown,
+            };
+
+            DriveInfo {
+                name: root.trim_end_matches('\\').to_string(),
+                path: root,
+                total_bytes: has_space.then_some(total_bytes),
+                free_bytes: has_space.then_some(free_bytes),
+                drive_type,
+            }
+        })
+        .collect()
+}
+
+#[tauri::command]
+fn get_drives() -> Vec<DriveInfo> {
+    list_drives()
+}
+
+#[tauri::command]
+fn get_filesystem_type(path: String) -> Result<String, String> {
+    let path = PathBuf::from(&path);
+    if !path.exists() {
+        return Err(format!("Path does not exist: {}", path.display()));
+    }
+    filesystem_type_for(&path)
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FileSizeEntry {
+    pub name: String,
+    pub path: String,
+    pub is_directory: bool,
+    pub size_bytes: u64,
+}
+
+#[tauri::command]
+fn get_children_sorted_by_size(path: String, desc: bool) -> Result<Vec<FileSizeEntry>, String> {
+    let read_dir = fs::read_dir(&path)
+        .map_err(|e| format!("Failed to read directory: {}", e))?;
+
+    let mut children: Vec<FileSizeEntry> = read_dir
+        .flatten()
+        .filter(|e| !e.file_name().to_string_lossy().starts_with('.'))
+        .filter_map(|e| {
+            let file_path = e.path();
+            // Directories report their own entry size; recursive totals are too slow for a listing
+            let metadata = fs::metadata(&file_path).ok()?;
+            Some(FileSizeEntry {
+                name: e.file_name().to_string_lossy().to_string(),
+                path: file_path.to_string_lossy().to_string(),
+                is_directory: metadata.is_dir(),
+                size_bytes: metadata.len(),
+            })
+        })
+        .collect();
+
+    children.sort_by(|a, b| {
+        let by_size = if desc {
+            b.size_bytes.cmp(&a.size_bytes)
+        } else {
+            a.size_bytes.cmp(&b.size_bytes)
+        };
+        by_size.then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+    });
+
+    Ok(children)
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HistogramBucket {
+    pub label: String,
+    pub count: usize,
+    pub size_bytes: u64,
+}
+
+fn size_range_label(size: u64) -> &'static str {
+    match size {
+        0..=1023 => "0-1KB",
+        1024..=102_399 => "1-100KB",
+        102_400..=1_048_575 => "100KB-1MB",
+        _ => ">1MB",
+    }
+}
+
+fn age_label(modified_secs: Option<u64>, now: u64) -> &'static str {
+    let age_days = match modified_secs {
+        Some(m) => now.saturating_sub(m) / (24 * 60 * 60),
+        None => return "older",
+    };
+    match age_days {
+        0 => "today",
+        1..=6 => "week",
+        7..=29 => "month",
+        30..=364 => "year",
+        _ => "older",
+    }
+}
+
+// Only files are counted; directory sizes aren't meaningful without a recursive walk
+#[tauri::command]
+fn get_dir_entry_histogram(path: String, bucket_by: String) -> Result<Vec<HistogramBucket>, String> {
+    if !["extension", "size_range", "age_days", "initial_letter"].contains(&bucket_by.as_str()) {
+        return Err(format!("Unsupported bucket: {}", bucket_by));
+    }
+
+    let read_dir = fs::read_dir(&path)
+        .map_err(|e| format!("Failed to read directory: {}", e))?;
+    let now = now_secs();
+    let mut buckets: HashMap<String, HistogramBucket> = HashMap::new();
+
+    for entry in read_dir.flatten() {
+        let metadata = match entry.metadata() {
+            Ok(m) if m.is_file() => m,
+            _ => continue,
+        };
+        let name = entry.file_name().to_string_lossy().to_string();
+
+        let label = match bucket_by.as_str() {
+            "extension" => Path::new(&name)
+                .extension()
+                .map(|e| e.to_string_lossy().to_lowercase())
+                .unwrap_or_else(|| "(none)".to_string()),
+            "size_range" => size_range_label(metadata.len()).to_string(),
+            "age_days" => {
+                let modified = metadata.modified().ok().and_then(system_time_secs);
+                age_label(modified, now).to_string()
+            }
+            _ => match name.chars().next() {
+                Some(c) if c.is_alphabetic() => c.to_uppercase().to_string(),
+                _ => "#".to_string(),
+            },
+        };
+
+        let bucket = buckets.entry(label.clone()).or_insert(HistogramBucket {
+            label,
+            count: 0,
+            size_bytes: 0,
+        });
+        bucket.count += 1;
+        bucket.size_bytes += metadata.len();
+    }
+
+    let mut buckets: Vec<HistogramBucket> = buckets.into_values().collect();
+    buckets.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.label.cmp(&b.label)));
+    Ok(buckets)
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ExtensionSummary {
+    pub extension: String,
+    pub count: usize,
+    pub total_bytes: u64,
+}
+
+fn collect_extension_summaries(dir: &Path, recursive: bool, summaries: &mut HashMap<String, ExtensionSummary>) {
+    let read_dir = match fs::read_dir(dir) {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+
+    for entry in read_dir.flatten() {
+        // DirEntry::metadata doesn't follow symlinks, so linked directories aren't walked
+        let metadata = match entry.metadata() {
+            Ok(m) => m,
+            Err(_) => continue,
+        };
+        if metadata.is_dir() {
+            if recursive {
+                collect_extension_summaries(&entry.path(), recursive, summaries);
+            }
+            continue;
+        }
+        if !metadata.is_file() {
+            continue;
+        }
+
+        let extension = entry.path()
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        let summary = summaries.entry(extension.clone()).or_insert(ExtensionSummary {
+            extension,
+            count: 0,
+            total_bytes: 0,
+        });
+        summary.count += 1;
+        summary.total_bytes += metadata.len();
+    }
+}
+
+#[tauri::command]
+fn get_unique_extensions_in_dir(path: String, recursive: bool) -> Vec<ExtensionSummary> {
+    const MAX_EXTENSIONS: usize = 200;
+
+    let mut summaries = HashMap::new();
+    collect_extension_summaries(Path::new(&path), recursive, &mut summaries);
+
+    let mut summaries: Vec<ExtensionSummary> = summaries.into_values().collect();
+    summaries.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.extension.cmp(&b.extension)));
+    summaries.truncate(MAX_EXTENSIONS);
+    summaries
+}
+
+#[tauri::command]
+fn get_path_watch_status(path: String, state: State<'_, WatcherState>) -> WatchStatus {
+    let watches = match state.watches.lock() {
+        Ok(w) => w,
+        Err(_) => return WatchStatus::from_info(&path, None),
+    };
+
+    // A path is also covered when it sits under a recursively watched directory
+    let info = watches.get(&path).or_else(|| {
+        watches.iter()
+            .filter(|(watched, info)| info.recursive && Path::new(&path).starts_with(watched))
+            .max_by_key(|(watched, _)| watched.len())
+            .map(|(_, info)| info)
+    });
+
+    WatchStatus::from_info(&path, info)
+}
+
+#[tauri::command]
+fn get_directory_change_count(path: String, state: State<'_, WatcherState>) -> u64 {
+    state.change_counts.lock()
+        .ok()
+        .and_then(|counts| counts.get(&path).map(|c| c.load(Ordering::Relaxed)))
+        .unwrap_or(0)
+}
+
+#[tauri::command]
+fn reset_directory_change_count(path: String, state: State<'_, WatcherState>) {
+    if let Ok(counts) = state.change_counts.lock() {
+        if let Some(count) = counts.get(&path) {
+            count.store(0, Ordering::Relaxed);
+        }
+    }
+}
+
+#[tauri::command]
+fn get_all_watch_statuses(state: State<'_, WatcherState>) -> Vec<WatchStatus> {
+    let watches = match state.watches.lock() {
+        Ok(w) => w,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut statuses: Vec<WatchStatus> = watches.iter()
+        .map(|(path, info)| WatchStatus::from_info(path, Some(info)))
+        .collect();
+    statuses.sort_by(|a, b| a.path.cmp(&b.path));
+    statuses
+}
+
+const INDEX_WATCH_ID_PREFIX: &str = "index-";
+// Events arriving within this window of each other are applied as one batch
+const INDEX_WATCH_DEBOUNCE: Duration = Duration::from_millis(200);
+const MAX_INDEX_WATCH_BATCH: usize = 5000;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct IndexChange {
+    pub added_paths: Vec<String>,
+    pub removed_paths: Vec<String>,
+}
+
+// A path that appeared, plus everything under it when a whole directory was moved in
+fn collect_arrived_paths(path: &Path, roots: &[PathBuf], options: &IndexOptions, out: &mut Vec<PathBuf>) {
+    if is_excluded_from_index(path, roots, options) {
+        return;
+    }
+    out.push(path.to_path_buf());
+
+    let descend = fs::symlink_metadata(path).is_ok_and(|m| m.is_dir())
+        && !path.file_name().is_some_and(|n| options.skips_directory(&n.to_string_lossy()));
+    if descend {
+        if let Ok(read_dir) = fs::read_dir(path) {
+            for entry in read_dir.flatten() {
+                collect_arrived_paths(&entry.path(), roots, options, out);
+            }
+        }
+    }
+}
+
+// Events only say which paths changed; whether each exists now decides if it is added or
+// removed, so the order of events within a batch doesn't matter
+fn apply_index_changes(app: &tauri::AppHandle, changed: HashSet<PathBuf>) {
+    let state = app.state::<IndexState>();
+    let roots: Vec<PathBuf> = match state.roots.lock() {
+        Ok(roots) => roots.iter().map(|r| r.path.clone()).collect(),
+        Err(_) => return,
+    };
+    let options = IndexOptions::from_config(&load_config(), &dirs::home_dir().unwrap_or_default());
+
+    let (present, mut gone): (Vec<PathBuf>, Vec<PathBuf>) = changed.into_iter()
+        .partition(|p| fs::symlink_metadata(p).is_ok());
+
+    let mut arrived = Vec::new();
+    for path in &present {
+        collect_arrived_paths(path, &roots, &options, &mut arrived);
+    }
+    let added = insert_index_entries(&state, &arrived).unwrap_or(0);
+
+    // Removing a directory already drops everything beneath it
+    gone.sort();
+    gone.dedup_by(|child, parent| child.starts_with(&*parent));
+    for path in &gone {
+        let _ = remove_index_entries(&state, path);
+    }
+
+    if added > 0 || !gone.is_empty() {
+        let _ = app.emit("index-changed", IndexChange {
+            added_paths: arrived.iter().map(|p| p.to_string_lossy().to_string()).collect(),
+            removed_paths: gone.iter().map(|p| p.to_string_lossy().to_string()).collect(),
+        });
+    }
+}
+
+// Watches every index root recursively, replacing any earlier index watch
+#[tauri::command]
+fn start_watching(app: tauri::AppHandle) -> Result<(), String> {
+    use notify::{EventKind, RecursiveMode, Watcher};
+
+    let roots: Vec<PathBuf> = app.state::<IndexState>().roots.lock()
+        .map_err(|e| e.to_string())?
+        .iter()
+        .map(|r| r.path.clone())
+        .collect();
+    if roots.is_empty() {
+        return Err("Nothing is indexed yet".to_string());
+    }
+
+    stop_watching(app.state())?;
+
+    let (tx, rx) = std::sync::mpsc::channel::<notify::Event>();
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+        if let Ok(event) = res {
+            let _ = tx.send(event);
+        }
+    }).map_err(|e| format!("Failed to create watcher: {}", e))?;
+
+    for root in &roots {
+        watcher.watch(root, RecursiveMode::Recursive)
+            .map_err(|e| format!("Failed to watch {}: {}", root.display(), e))?;
+    }
+
+    let watcher_state: State<'_, WatcherState> = app.state();
+    if let Ok(mut watches) = watcher_state.watches.lock() {
+        let active_since = now_secs();
+        for (i, root) in roots.iter().enumerate() {
+            watches.insert(root.to_string_lossy().to_string(), WatchInfo {
+                watch_id: format!("{}{}", INDEX_WATCH_ID_PREFIX, i),
+                recursive: true,
+                active_since,
+            });
+        }
+    }
+    *watcher_state.index_watcher.lock().map_err(|e| e.to_string())? = Some(watcher);
+
+    // Ends once the watcher is dropped and the channel closes
+    let app_handle = app.clone();
+    thread::spawn(move || {
+        while let Ok(first) = rx.recv() {
+            let mut changed = HashSet::new();
+            let mut next = Some(first);
+            while let Some(event) = next.take() {
+                for path in &event.paths {
+                    app_handle.state::<WatcherState>().record_change(path);
+                }
+                if matches!(event.kind, EventKind::Create(_) | EventKind::Remove(_)
+                    | EventKind::Modify(notify::event::ModifyKind::Name(_)))
+                {
+                    changed.extend(event.paths);
+                }
+                if changed.len() < MAX_INDEX_WATCH_BATCH {
+                    next = rx.recv_timeout(INDEX_WATCH_DEBOUNCE).ok();
+                }
+            }
+
+            if !changed.is_empty() {
+                apply_index_changes(&app_handle, changed);
+            }
+        }
+    });
+
+    Ok(())
+}
+
+#[tauri::command]
+fn stop_watching(state: State<'_, WatcherState>) -> Result<(), String> {
+    state.index_watcher.lock().map_err(|e| e.to_string())?.take();
+    if let Ok(mut watches) = state.watches.lock() {
+        watches.retain(|_, info| !info.watch_id.starts_with(INDEX_WATCH_ID_PREFIX));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DependencyStatus {
+    pub git: bool,
+    pub lsof: bool,
+    pub opener: bool,
+    pub terminal: Option<String>,
+    pub os_file_manager: String,
+}
+
+// Look an executable up on PATH, like `which`
+fn find_in_path(name: &str) -> Option<PathBuf> {
+    let path_var = std::env::var_os("PATH")?;
+    std::env::split_paths(&path_var).find_map(|dir| {
+        let candidate = dir.join(name);
+        if candidate.is_file() {
+            return Some(candidate);
+        }
+        #[cfg(windows)]
+        {
+            let with_exe = dir.join(format!("{}.exe", name));
+            if with_exe.is_file() {
+                return Some(with_exe);
+            }
+        }
+        None
+    })
+}
+
+#[cfg(target_os = "macos")]
+fn detect_terminal() -> Option<String> {
+    if Path::new("/Applications/iTerm.app").exists() {
+        Some("iTerm".to_string())
+    } else {
+        Some("Terminal".to_string())
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn detect_terminal() -> Option<String> {
+    if let Ok(terminal) = std::env::var("TERMINAL") {
+        if find_in_path(&terminal).is_some() || Path::new(&terminal).is_file() {
+            return Some(terminal);
+        }
+    }
+    ["x-terminal-emulator", "gnome-terminal", "konsole", "xfce4-terminal", "alacritty", "kitty", "xterm"]
+        .iter()
+        .find(|t| find_in_path(t).is_some())
+        .map(|t| t.to_string())
+}
+
+#[cfg(windows)]
+fn detect_terminal() -> Option<String> {
+    if find_in_path("wt").is_some() {
+        Some("wt".to_string())
+    } else {
+        Some("cmd".to_string())
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn terminal_command(terminal: &str, dir: &Path) -> std::process::Command {
+    let mut c = std::process::Command::new("open");
+    c.arg("-a").arg(terminal).arg(dir);
+    c
+}
+
+#[cfg(target_os = "linux")]
+fn terminal_command(terminal: &str, dir: &Path) -> std::process::Command {
+    let mut c = std::process::Command::new(terminal);
+    c.current_dir(dir);
+    c
+}
+
+#[cfg(windows)]
+fn terminal_command(terminal: &str, dir: &Path) -> std::process::Command {
+    let mut c = match terminal {
+        "wt" => {
+            let mut c = std::process::Command::new("wt");
+            c.arg("-d").arg(dir);
+            c
+        }
+        // `start` gives cmd its own console window instead of attaching to ours
+        "cmd" => {
+            let mut c = std::process::Command::new("cmd");
+            c.args(["/C", "start", "cmd"]);
+            c
+        }
+        other => std::process::Command::new(other),
+    };
+    c.current_dir(dir);
+    c
+}
+
+// Async so terminal detection and process spawning stay off the main thread
+#[tauri::command]
+async fn open_in_terminal(path: String) -> Result<(), String> {
+    let dir = Path::new(&path);
+    if !dir.is_dir() {
+        return Err(format!("Not a directory: {}", path));
+    }
+    let terminal = load_config()
+        .preferred_terminal
+        .filter(|t| !t.trim().is_empty())
+        .or_else(detect_terminal)
+        .unwrap_or_else(|| "xterm".to_string());
+
+    let mut child = terminal_command(&terminal, dir)
+        .spawn()
+        .map_err(|e| format!("Failed to launch {}: {}", terminal, e))?;
+    // Reap the launcher when it exits so it doesn't linger as a zombie
+    thread::spawn(move || {
+        let _ = child.wait();
+    });
+    Ok(())
+}
+
+fn detect_file_manager() -> String {
+    if cfg!(target_os = "macos") {
+        "Finder".to_string()
+    } else if cfg!(windows) {
+        "Explorer".to_string()
+    } else {
+        ["nautilus", "dolphin", "thunar", "nemo", "pcmanfm", "xdg-open"]
+            .iter()
+            .find(|fm| find_in_path(fm).is_some())
+            .map(|fm| fm.to_string())
+            .unwrap_or_default()
+    }
+}
+
+static DEPENDENCY_STATUS: OnceLock<DependencyStatus> = OnceLock::new();
+
+// Probed once per process; installing a tool requires an app restart to be noticed
+#[tauri::command]
+fn check_dependencies() -> DependencyStatus {
+    DEPENDENCY_STATUS
+        .get_or_init(|| {
+            let opener = if cfg!(target_os = "macos") {
+                "open"
+            } else if cfg!(windows) {
+                "explorer"
+            } else {
+                "xdg-open"
+            };
+            DependencyStatus {
+                git: find_in_path("git").is_some(),
+                lsof: find_in_path("lsof").is_some(),
+                opener: find_in_path(opener).is_some(),
+                terminal: detect_terminal(),
+                os_file_manager: detect_file_manager(),
+            }
+        })
+        .clone()
+}
+
+// ERROR_NO_ASSOCIATION, returned by ShellExecuteEx when no handler is registered
+#[cfg(windows)]
+const NO_ASSOCIATION_OS_ERROR: i32 = 1155;
+
+fn is_no_application_error(e: &std::io::Error) -> bool {
+    #[cfg(windows)]
+    if e.raw_os_error() == Some(NO_ASSOCIATION_OS_ERROR) {
+        return true;
+    }
+    // On Unix the opener reports NotFound when no launcher (xdg-open, gio, ...) could be run
+    e.kind() == std::io::ErrorKind::NotFound
+}
+
+#[tauri::command]
+fn open_with_default_app(path: String) -> Result<(), String> {
+    let target = Path::new(&path);
+    let result = if !target.exists() {
+        Err(format!("File not found: {}", path))
+    } else {
+        tauri_plugin_opener::open_path(target, None::<&str>).map_err(|e| match e {
+            tauri_plugin_opener::Error::Io(ref io) if is_no_application_error(io) => {
+                format!("No application registered to open {}: {}", path, io)
+            }
+            e => format!("OS error opening {}: {}", path, e),
+        })
+    };
+    if let Err(ref e) = result {
+        append_debug_log(&format!("open_with_default_app {}: {}", path, e));
+    }
+    result
+}
+
+const MAX_TAB_ACCESS_HISTORY: usize = 100;
+
+#[tauri::command]
+fn record_tab_access(tab_id: String, state: State<'_, TabState>) -> Result<(), String> {
+    let order: Vec<String> = {
+        let mut order = state.tab_access_order.lock().map_err(|e| e.to_string())?;
+        order.retain(|id| id != &tab_id);
+        order.push_front(tab_id);
+        order.truncate(MAX_TAB_ACCESS_HISTORY);
+        order.iter().cloned().collect()
+    };
+
+    let mut config = load_config();
+    config.active_tab_order = order;
+    write_config(config)
+}
+
+#[tauri::command]
+fn get_tab_access_order(state: State<'_, TabState>) -> Vec<String> {
+    state.tab_access_order.lock()
+        .map(|order| order.iter().cloned().collect())
+        .unwrap_or_default()
+}
+
+// "/a/b/" and "/a/./b" point at the same folder, so compare normalized paths
+fn tab_path_key(path: &str) -> PathBuf {
+    normalize_lexically(Path::new(path))
+}
+
+#[tauri::command]
+fn get_tab_duplicate_paths() -> Vec<String> {
+    let mut counts: HashMap<PathBuf, (String, usize)> = HashMap::new();
+    for tab in load_config().tabs.unwrap_or_default() {
+        counts.entry(tab_path_key(&tab.path))
+            .or_insert_with(|| (tab.path.clone(), 0))
+            .1 += 1;
+    }
+
+    let mut duplicates: Vec<String> = counts.into_values()
+        .filter(|(_, count)| *count > 1)
+        .map(|(path, _)| path)
+        .collect();
+    duplicates.sort();
+    duplicates
+}
+
+#[tauri::command]
+fn get_tabs_for_path(path: String) -> Vec<Tab> {
+    let key = tab_path_key(&path);
+    load_config().tabs.unwrap_or_default()
+        .into_iter()
+        .filter(|tab| tab_path_key(&tab.path) == key)
+        .collect()
+}
+
+// Kills the child and, on Unix, everything it started: the child leads its own process group
+fn kill_process_group(child: &mut std::process::Child) {
+    #[cfg(unix)]
+    unsafe { libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL) };
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+// Run a command, killing it if it outlives `timeout`. The deadline also covers collecting
+// output, since a backgrounded grandchild can hold the pipes open after the child exits.
+fn run_command_with_timeout(
+    mut command: std::process::Command,
+    timeout: Duration,
+) -> Result<std::process::Output, String> {
+    use std::io::Read;
+    use std::process::Stdio;
+    use std::sync::mpsc;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to start command: {}", e))?;
+
+    // Drain the pipes on their own threads so a chatty child can't block on a full pipe
+    let spawn_reader = |pipe: Option<Box<dyn Read + Send>>| {
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            let mut buf = Vec::new();
+            if let Some(mut pipe) = pipe {
+                let _ = pipe.read_to_end(&mut buf);
+            }
+            let _ = tx.send(buf);
+        });
+        rx
+    };
+    let stdout_reader = spawn_reader(child.stdout.take().map(|p| Box::new(p) as Box<dyn Read + Send>));
+    let stderr_reader = spawn_reader(child.stderr.take().map(|p| Box::new(p) as Box<dyn Read + Send>));
+
+    let timed_out = || format!("Command timed out after {} seconds", timeout.as_secs());
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if Instant::now() >= deadline => {
+                kill_process_group(&mut child);
+                return Err(timed_out());
+            }
+            Ok(None) => thread::sleep(Duration::from_millis(20)),
+            Err(e) => return Err(format!("Failed to wait for command: {}", e)),
+        }
+    };
+
+    let mut collect = |reader: mpsc::Receiver<Vec<u8>>| {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        reader.recv_timeout(remaining).map_err(|_| {
+            kill_process_group(&mut child);
+            timed_out()
+        })
+    };
+    let stdout = collect(stdout_reader)?;
+    let stderr = collect(stderr_reader)?;
+
+    Ok(std::process::Output { status, stdout, stderr })
+}
+
+// Programs that only read. Anything else, including every interpreter and shell, is refused.
+// git is left out: a repository's own config (diff.external, textconv and filter drivers,
+// core.fsmonitor) can make even `git status` run arbitrary programs.
+const PREVIEW_ALLOWED_COMMANDS: [&str; 15] = [
+    "ls", "cat", "head", "tail", "wc", "pwd", "echo", "stat", "du", "df", "whoami", "uname", "which",
+    "dir", "type",
+];
+// Shell syntax is never interpreted; rejecting these keeps cmd.exe from interpreting it either
+const PREVIEW_REJECTED_CHARS: &str = "|&;<>()$`^%!\n\r";
+
+// Splits a preview command into arguments, honouring single and double quotes but nothing
+// else, and checks it against the allowlist
+fn parse_preview_command(command: &str) -> Result<Vec<String>, String> {
+    if let Some(c) = command.chars().find(|c| PREVIEW_REJECTED_CHARS.contains(*c)) {
+        return Err(format!("`{}` is not allowed in previews", c.escape_default()));
+    }
+
+    let mut args = Vec::new();
+    let mut current: Option<String> = None;
+    let mut quote: Option<char> = None;
+    for c in command.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), c) => current.get_or_insert_with(String::new).push(c),
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                current.get_or_insert_with(String::new);
+            }
+            (None, c) if c.is_whitespace() => args.extend(current.take()),
+            (None, c) => current.get_or_insert_with(String::new).push(c),
+        }
+    }
+    if quote.is_some() {
+        return Err("Unterminated quote".to_string());
+    }
+    args.extend(current);
+
+    let program = args.first().ok_or("Command is empty")?;
+    // A path could point at any executable, so programs must be found on PATH
+    if program.contains(['/', '\\']) || !PREVIEW_ALLOWED_COMMANDS.contains(&program.as_str()) {
+        return Err(format!("`{}` is not a read-only command", program));
+    }
+    Ok(args)
+}
+
+#[tauri::command]
+fn get_terminal_preview(command: String, cwd: String) -> Result<String, String> {
+    const MAX_COMMAND_LEN: usize = 500;
+    const MAX_OUTPUT_CHARS: usize = 2000;
+
+    if command.trim().is_empty() {
+        return Err("Command is empty".to_string());
+    }
+    if command.len() > MAX_COMMAND_LEN {
+        return Err(format!("Command is too long to preview (max {} characters)", MAX_COMMAND_LEN));
+    }
+    let args = parse_preview_command(&command).map_err(|reason| format!("Preview disabled: {}", reason))?;
+    if !Path::new(&cwd).is_dir() {
+        return Err(format!("Working directory does not exist: {}", cwd));
+    }
+
+    // Run directly rather than through a shell; only dir and type need cmd, being built into it
+    let mut process = if cfg!(windows) && (args[0] == "dir" || args[0] == "type") {
+        let mut c = std::process::Command::new("cmd");
+        c.arg("/C").args(&args);
+        c
+    } else {
+        let mut c = std::process::Command::new(&args[0]);
+        c.args(&args[1..]);
+        c
+    };
+    process.current_dir(&cwd);
+
+    let output = run_command_with_timeout(process, Duration::from_secs(3))?;
+    Ok(String::from_utf8_lossy(&output.stdout).chars().take(MAX_OUTPUT_CHARS).collect())
+}
+
+// Walk up from a path to the directory holding `.git` (a directory, or a file for worktrees)
+fn find_git_repo_root(path: &Path) -> Option<PathBuf> {
+    path.ancestors()
+        .find(|dir| dir.join(".git").exists())
+        .map(|dir| dir.to_path_buf())
+}
+
+// Keyed by (path, mtime) so an edited file gets looked up again
+type LastCommitCache = HashMap<(String, u64), Option<String>>;
+
+static LAST_COMMIT_CACHE: OnceLock<Mutex<LastCommitCache>> = OnceLock::new();
+
+const MAX_LAST_COMMIT_CACHE: usize = 1000;
+
+#[tauri::command]
+fn get_path_last_commit_message(path: String) -> Result<Option<String>, String> {
+    let target = PathBuf::from(&path);
+    let modified = fs::metadata(&target)
+        .map_err(|e| format!("Failed to read metadata: {}", e))?
+        .modified()
+        .ok()
+        .and_then(system_time_secs)
+        .unwrap_or(0);
+
+    let cache = LAST_COMMIT_CACHE.get_or_init(|| Mutex::new(HashMap::new()));
+    let key = (path.clone(), modified);
+    if let Some(cached) = cache.lock().map_err(|e| e.to_string())?.get(&key) {
+        return Ok(cached.clone());
+    }
+
+    let repo_root = match find_git_repo_root(&target) {
+        Some(root) => root,
+        None => return Ok(None),
+    };
+    if find_in_path("git").is_none() {
+        return Ok(None);
+    }
+    let relative = target.strip_prefix(&repo_root)
+        .map_err(|_| format!("Path is not inside its repository: {}", path))?;
+
+    let mut git = std::process::Command::new("git");
+    git.arg("-C").arg(&repo_root)
+        .args(["log", "--format=%s", "-n", "1", "--"])
+        .arg(relative);
+    let output = run_command_with_timeout(git, Duration::from_secs(5))?;
+    if !output.status.success() {
+        return Err(format!("git log failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+
+    // Untracked files produce no output
+    let message = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    let message = (!message.is_empty()).then_some(message);
+
+    let mut cache = cache.lock().map_err(|e| e.to_string())?;
+    if cache.len() >= MAX_LAST_COMMIT_CACHE {
+        cache.clear();
+    }
+    cache.insert(key, message.clone());
+    Ok(message)
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GitBlameLine {
+    pub commit_hash: String,
+    pub author: String,
+    pub timestamp: u64,
+    pub line_content: String,
+}
+
+fn parse_blame_porcelain(output: &str) -> Option<GitBlameLine> {
+    let mut lines = output.lines();
+    let commit_hash = lines.next()?.split_whitespace().next()?.to_string();
+    let mut author = String::new();
+    let mut timestamp = 0;
+
+    for line in lines {
+        // The line itself comes last, prefixed with a tab
+        if let Some(content) = line.strip_prefix('\t') {
+            return Some(GitBlameLine { commit_hash, author, timestamp, line_content: content.to_string() });
+        }
+        if let Some(name) = line.strip_prefix("author ") {
+            author = name.to_string();
+        } else if let Some(time) = line.strip_prefix("author-time ") {
+            timestamp = time.trim().parse().unwrap_or(0);
+        }
+    }
+    None
+}
+
+#[tauri::command]
+fn get_path_git_blame_line(path: String, line: usize) -> Result<GitBlameLine, String> {
+    if line == 0 {
+        return Err("Line numbers start at 1".to_string());
+    }
+
+    let target = PathBuf::from(&path);
+    if !target.is_file() {
+        return Err(format!("Not a file: {}", path));
+    }
+    let repo_root = find_git_repo_root(&target)
+        .ok_or_else(|| format!("File is not in a git repository: {}", path))?;
+    if find_in_path("git").is_none() {
+        return Err("git is not installed".to_string());
+    }
+    let relative = target.strip_prefix(&repo_root)
+        .map_err(|_| format!("Path is not inside its repository: {}", path))?;
+
+    let mut git = std::process::Command::new("git");
+    git.arg("-C").arg(&repo_root)
+        .arg("blame")
+        .arg("-L")
+        .arg(format!("{},{}", line, line))
+        .arg("--porcelain")
+        .arg("--")
+        .arg(relative);
+    let output = run_command_with_timeout(git, Duration::from_secs(3))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if stderr.contains("no such path") {
+            return Err(format!("File is not tracked by git: {}", path));
+        }
+        return Err(format!("git blame failed: {}", stderr.trim()));
+    }
+
+    parse_blame_porcelain(&String::from_utf8_lossy(&output.stdout))
+        .ok_or_else(|| "Failed to parse git blame output".to_string())
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProcessSummary {
+    pub pid: u32,
+    pub name: String,
+    pub cpu_percent: f32,
+    pub memory_bytes: u64,
+    pub exe_path: Option<String>,
+}
+
+// The processes using the most memory, with CPU usage sampled over a short interval
+#[tauri::command]
+async fn list_running_processes() -> Result<Vec<ProcessSummary>, String> {
+    use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
+
+    const MAX_PROCESSES: usize = 50;
+
+    let sample = || {
+        let refresh = ProcessRefreshKind::nothing()
+            .with_cpu()
+            .with_memory()
+            .with_exe(UpdateKind::OnlyIfNotSet);
+        let mut system = System::new();
+        // CPU usage is a delta, so it needs two samples at least MINIMUM_CPU_UPDATE_INTERVAL apart
+        system.refresh_processes_specifics(ProcessesToUpdate::All, true, refresh);
+        thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+        system.refresh_processes_specifics(ProcessesToUpdate::All, true, refresh);
+
+        system.processes()
+            .iter()
+            .map(|(pid, process)| ProcessSummary {
+                pid: pid.as_u32(),
+                name: process.name().to_string_lossy().to_string(),
+                cpu_percent: process.cpu_usage(),
+                memory_bytes: process.memory(),
+                exe_path: process.exe().map(|p| p.to_string_lossy().to_string()),
+            })
+            .collect::<Vec<ProcessSummary>>()
+    };
+
+    let mut processes = tauri::async_runtime::spawn_blocking(sample)
+        .await
+        .map_err(|e| format!("Process sampling failed: {}", e))?;
+    if processes.is_empty() {
+        return Err("Process listing is not supported on this platform".to_string());
+    }
+
+    processes.sort_by_key(|p| std::cmp::Reverse(p.memory_bytes));
+    processes.truncate(MAX_PROCESSES);
+    Ok(processes)
+}
+
+#[tauri::command]
+async fn toggle_window_visibility(app: tauri::AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        if window.is_visible().unwrap_or(false) {
+            let _ = window.hide();
+        } else {
+            let _ = window.show();
+            let _ = window.set_focus();
+        }
+    }
+}
+
+fn get_index_path() -> PathBuf {
+    get_config_dir().join("index.msgpack")
+}
+
+// Where indexes were saved before the switch to MessagePack
+fn get_legacy_index_path() -> PathBuf {
+    get_config_dir().join("index.json")
+}
+
+// Every index write shares one temp file, so concurrent saves must take turns
+static INDEX_SAVE_LOCK: Mutex<()> = Mutex::new(());
+
+// Named (map) encoding, so entries saved before a field existed still load with its default
+fn write_index_file(entries: &[IndexEntry]) -> Result<(), String> {
+    let bytes = rmp_serde::to_vec_named(entries)
+        .map_err(|e| format!("Failed to serialize index: {}", e))?;
+    fs::create_dir_all(get_config_dir())
+        .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    let _guard = INDEX_SAVE_LOCK.lock().map_err(|e| e.to_string())?;
+    write_file_atomic(&get_index_path(), &bytes)
+}
+
+// Writes the in-memory index and records when, for the UI's "last saved" display
+fn save_index(state: &IndexState) -> Result<(), String> {
+    // Cleared before the write so edits made while it runs are picked up by the next save
+    state.unsaved_changes.store(false, Ordering::Relaxed);
+    let result = state.entries.read()
+        .map_err(|e| e.to_string())
+        .and_then(|entries| write_index_file(&entries));
+    match result {
+        Ok(()) => {
+            if let Ok(mut progress) = state.progress.lock() {
+                progress.last_saved_at = Some(now_secs());
+            }
+        }
+        Err(_) => state.unsaved_changes.store(true, Ordering::Relaxed),
+    }
+    result
+}
+
+// The interval is re-read every cycle, so config changes apply without a restart
+fn spawn_index_autosave(app: tauri::AppHandle) {
+    const DISABLED_RECHECK: Duration = Duration::from_secs(60);
+
+    thread::spawn(move || loop {
+        let interval = match load_config().auto_save_interval_secs {
+            Some(secs) if secs > 0 => Duration::from_secs(secs),
+            _ => {
+                thread::sleep(DISABLED_RECHECK);
+                continue;
+            }
+        };
+        thread::sleep(interval);
+
+        let state: State<'_, IndexState> = app.state();
+        // A running walk saves its own result when it finishes
+        let is_indexing = state.is_indexing.lock().map(|i| *i).unwrap_or(true);
+        if !is_indexing && state.unsaved_changes.load(Ordering::Relaxed) {
+            let _ = save_index(&state);
+        }
+    });
+}
+
+// Snapshots are taken with the lock held, so a later snapshot is never overwritten by an
+// earlier one that was still being written
+static SEARCH_HISTORY_SAVE_LOCK: Mutex<()> = Mutex::new(());
+
+// The history mutex is only held while cloning, never during the write
+fn save_search_history(state: &IndexState) -> Result<(), String> {
+    let _guard = SEARCH_HISTORY_SAVE_LOCK.lock().map_err(|e| e.to_string())?;
+    state.unsaved_search_history.store(false, Ordering::Relaxed);
+    let result = state.search_history.lock()
+        .map(|history| history.clone())
+        .map_err(|e| e.to_string())
+        .and_then(|history| history.save());
+    if result.is_err() {
+        state.unsaved_search_history.store(true, Ordering::Relaxed);
+    }
+    result
+}
+
+// Searches only mark the history dirty; this writes it out a few seconds later
+fn spawn_search_history_autosave(app: tauri::AppHandle) {
+    const SAVE_DELAY: Duration = Duration::from_secs(2);
+
+    thread::spawn(move || loop {
+        thread::sleep(SAVE_DELAY);
+        let state: State<'_, IndexState> = app.state();
+        if state.unsaved_search_history.load(Ordering::Relaxed) {
+            let _ = save_search_history(&state);
+        }
+    });
+}
+
+fn read_legacy_index_file() -> Result<Vec<IndexEntry>, String> {
+    let content = fs::read_to_string(get_legacy_index_path())
+        .map_err(|e| format!("Failed to read JSON index: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse JSON index: {}", e))
+}
+
+fn get_search_history_path() -> PathBuf {
+    get_config_dir().join("search_history.json")
+}
+
+fn get_recent_paths_path() -> PathBuf {
+    get_config_dir().join("recent.json")
+}
+
+fn get_bookmarks_path() -> PathBuf {
+    get_config_dir().join("bookmarks.json")
+}
+
+fn get_debug_log_path() -> PathBuf {
+    get_config_dir().join("debug.log")
+}
+
+// Best effort: a failure to log must never mask the error being logged
+fn append_debug_log(message: &str) {
+    use std::io::Write;
+    let _ = fs::create_dir_all(get_config_dir());
+    if let Ok(mut file) = fs::OpenOptions::new().create(true).append(true).open(get_debug_log_path()) {
+        let _ = writeln!(file, "[{}] {}", now_secs(), message);
+    }
+}
+
+fn get_themes_dir() -> PathBuf {
+    get_config_dir().join("themes")
+}
+
+// Rough heap footprint of the in-memory index: struct sizes plus string buffers
+fn estimate_index_memory_bytes(state: &IndexState) -> u64 {
+    let entries_bytes: usize = state.entries.read()
+        .map(|entries| entries.iter()
+            .map(|e| std::mem::size_of::<IndexEntry>()
+                + e.name.capacity()
+                + e.path.capacity()
+                + e.parent_folder.capacity())
+            .sum())
+        .unwrap_or(0);
+    let lower_names_bytes: usize = state.lower_names.read()
+        .map(|names| names.iter()
+            .map(|n| std::mem::size_of::<String>() + n.capacity())
+            .sum())
+        .unwrap_or(0);
+
+    (entries_bytes + lower_names_bytes) as u64
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AppDataUsage {
+    pub config_bytes: u64,
+    pub index_bytes: u64,
+    pub themes_bytes: u64,
+    pub history_bytes: u64,
+    pub total_bytes: u64,
+    pub index_memory_bytes: u64,
+    pub config_dir: String,
+}
+
+fn file_len(path: &Path) -> u64 {
+    fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+}
+
+#[tauri::command]
+fn get_app_data_usage(state: State<'_, IndexState>) -> AppDataUsage {
+    let config_dir = get_config_dir();
+    let config_path = get_config_path();
+
+    AppDataUsage {
+        config_bytes: file_len(&config_path) + file_len(&get_config_backup_path()),
+        index_bytes: file_len(&get_index_path()) + file_len(&get_legacy_index_path()),
+        themes_bytes: dir_size_bytes(&get_themes_dir()),
+        history_bytes: file_len(&get_search_history_path()),
+        total_bytes: dir_size_bytes(&config_dir),
+        index_memory_bytes: estimate_index_memory_bytes(&state),
+        config_dir: config_dir.to_string_lossy().to_string(),
+    }
+}
+
+// Directories that are indexed themselves but never descended into
+const DEFAULT_SKIP_DIRECTORIES: [&str; 14] = [
+    "node_modules", "target", ".git", "dist", "build", ".next", "vendor", "__pycache__",
+    ".venv", "venv", ".cargo", "Library", ".Trash", "Applications",
+];
+
+#[tauri::command]
+fn get_default_skip_directories() -> Vec<String> {
+    DEFAULT_SKIP_DIRECTORIES.iter().map(|d| d.to_string()).collect()
+}
+
+// The parts of Config that decide what index_directory visits and records
+struct IndexOptions {
+    skip_directories: Vec<String>,
+    include_directories: Vec<PathBuf>,
+    exclude_set: globset::GlobSet,
+    record_access_times: bool,
+}
+
+impl IndexOptions {
+    fn from_config(config: &Config, home: &Path) -> Self {
+        IndexOptions {
+            skip_directories: config.skip_directories.clone()
+                .unwrap_or_else(get_default_skip_directories),
+            include_directories: config.include_directories.iter()
+                .flatten()
+                .map(|dir| normalize_lexically(&home.join(dir)))
+                .collect(),
+            exclude_set: build_exclude_set(&config.exclude_patterns, home),
+            record_access_times: config.index_access_times,
+        }
+    }
+
+    fn skips_directory(&self, name: &str) -> bool {
+        self.skip_directories.iter().any(|d| d == name)
+    }
+
+    fn is_included(&self, path: &Path) -> bool {
+        self.include_directories.iter().any(|dir| path.starts_with(dir))
+    }
+
+    fn matches_exclude_pattern(&self, path: &Path) -> bool {
+        self.exclude_set.is_match(path)
+    }
+}
+
+// Invalid patterns are skipped rather than failing the whole index
+fn build_exclude_set(patterns: &[String], home: &Path) -> globset::GlobSet {
+    let home = home.to_string_lossy().replace('\\', "/");
+    let mut builder = globset::GlobSetBuilder::new();
+
+    for pattern in patterns.iter().map(|p| p.trim()).filter(|p| !p.is_empty()) {
+        let pattern = if let Some(rest) = pattern.strip_prefix("~/") {
+            format!("{}/{}", home.trim_end_matches('/'), rest)
+        } else if !pattern.contains('/') {
+            format!("**/{}", pattern)
+        } else {
+            pattern.to_string()
+        };
+
+        if let Ok(glob) = globset::GlobBuilder::new(&pattern).literal_separator(true).build() {
+            builder.add(glob);
+        }
+    }
+
+    builder.build().unwrap_or_else(|_| globset::GlobSet::empty())
+}
+
+// Whether the walk from `root` (hidden entries skipped) would leave `target` out
+fn is_excluded_from_walk(root: &Path, target: &Path, options: &IndexOptions) -> bool {
+    // Anything outside the index root is never visited
+    let relative = match target.strip_prefix(root) {
+        Ok(r) => r,
+        Err(_) => return true,
+    };
+
+    let names: Vec<String> = relative.components()
+        .filter_map(|c| match c {
+            Component::Normal(name) => Some(name.to_string_lossy().to_string()),
+            _ => None,
+        })
+        .collect();
+
+    // Each ancestor is checked by its own full path, as the walk would see it
+    let mut current = root.to_path_buf();
+    names.iter().enumerate().any(|(i, name)| {
+        current.push(name);
+        let is_ancestor = i + 1 < names.len();
+        name.starts_with('.')
+            || options.matches_exclude_pattern(&current)
+            || (is_ancestor && options.skips_directory(name))
+    })
+}
+
+#[tauri::command]
+fn get_path_is_excluded(path: String, state: State<'_, IndexState>) -> bool {
+    let home = match dirs::home_dir() {
+        Some(h) => h,
+        None => return true,
+    };
+    let target = normalize_lexically(Path::new(&path));
+    let config = load_config();
+
+    // Before the first index, judge against the roots the next index would use
+    let roots: Vec<PathBuf> = match state.roots.lock() {
+        Ok(roots) if !roots.is_empty() => roots.iter().map(|r| r.path.clone()).collect(),
+        _ => configured_index_roots(&config, &home),
+    };
+    let options = IndexOptions::from_config(&config, &home);
+
+    is_excluded_from_index(&target, &roots, &options)
+}
+
+fn is_excluded_from_index(target: &Path, roots: &[PathBuf], options: &IndexOptions) -> bool {
+    !options.is_included(target)
+        && roots.iter().all(|root| is_excluded_from_walk(&normalize_lexically(root), target, options))
+}
+
+fn entry_extension(name: &str) -> String {
+    Path::new(name)
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
+
+fn build_index_entry(file_path: &Path, name: &str, parent_folder: &str, record_access_times: bool) -> IndexEntry {
+    let metadata = fs::metadata(file_path).ok();
+    let accessed_secs = if record_access_times {
+        metadata.as_ref()
+            .and_then(|m| m.accessed().ok())
+            .and_then(system_time_secs)
+    } else {
+        None
+    };
+
+    let is_directory = metadata.as_ref().map(|m| m.is_dir()).unwrap_or(false);
+
+    IndexEntry {
+        name: name.to_string(),
+        path: file_path.to_string_lossy().to_string(),
+        is_directory,
+        parent_folder: parent_folder.to_string(),
+        modified_secs: metadata.as_ref()
+            .and_then(|m| m.modified().ok())
+            .and_then(system_time_secs),
+        created_secs: metadata.as_ref()
+            .and_then(|m| m.created().ok())
+            .and_then(system_time_secs),
+        accessed_secs,
+        size_bytes: metadata.as_ref().filter(|m| m.is_file()).map(|m| m.len()),
+        extension: if is_directory { String::new() } else { entry_extension(name) },
+        root: String::new(),
+        indexed_at: now_secs(),
+    }
+}
+
+// Build entries for the immediate children of a directory, applying the configured exclusions
+fn read_shallow_entries(path: &Path) -> Result<Vec<IndexEntry>, String> {
+    let read_dir = fs::read_dir(path)
+        .map_err(|e| format!("Failed to read directory: {}", e))?;
+    let parent_folder = path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "~".to_string());
+    let options = IndexOptions::from_config(&load_config(), &dirs::home_dir().unwrap_or_default());
+
+    Ok(read_dir.flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let entry_path = entry.path();
+            if name.starts_with('.') || options.matches_exclude_pattern(&entry_path) {
+                return None;
+            }
+            Some(build_index_entry(&entry_path, &name, &parent_folder, options.record_access_times))
+        })
+        .collect())
+}
+
+// Index only the immediate children of a directory into the live index.
+// With `refresh`, existing children are dropped first so their metadata is re-read.
+// Returns how many entries weren't in the index before.
+fn index_directory_shallow(path: &Path, state: &IndexState, refresh: bool) -> Result<usize, String> {
+    // A running walk replaces the whole index when it finishes, so changes now would be lost
+    let is_complete = state.progress.lock().map(|p| p.is_complete).unwrap_or(false);
+    if !is_complete {
+        return Ok(0);
+    }
+
+    let mut children = read_shallow_entries(path)?;
+    let root_tag = state.roots.lock().ok()
+        .and_then(|roots| root_for_path(&roots, path).map(|r| r.path.to_string_lossy().to_string()))
+        .unwrap_or_default();
+    for child in &mut children {
+        child.root = root_tag.clone();
+    }
+
+    let mut entries = state.entries.write().map_err(|e| e.to_string())?;
+    let mut lower_names = state.lower_names.write().map_err(|e| e.to_string())?;
+
+    // Path -> indexed_at, so refreshed entries keep the time they first entered the index
+    let mut known_paths: HashMap<String, u64> = HashMap::new();
+    if refresh {
+        let mut i = 0;
+        while i < entries.len() {
+            if Path::new(&entries[i].path).parent() == Some(path) {
+                let removed = entries.swap_remove(i);
+                known_paths.insert(removed.path, removed.indexed_at);
+                lower_names.swap_remove(i);
+            } else {
+                i += 1;
+            }
+        }
+    } else {
+        known_paths.extend(entries.iter()
+            .filter(|e| Path::new(&e.path).parent() == Some(path))
+            .map(|e| (e.path.clone(), e.indexed_at)));
+    }
+
+    let mut added = 0;
+    for mut child in children {
+        let known_indexed_at = known_paths.get(&child.path).copied();
+        let is_new = known_indexed_at.is_none();
+        match known_indexed_at {
+            Some(indexed_at) => child.indexed_at = indexed_at,
+            None => added += 1,
+        }
+        if is_new || refresh {
+            lower_names.push(child.name.to_lowercase());
+            entries.push(child);
+        }
+    }
+    if added > 0 || refresh {
+        state.unsaved_changes.store(true, Ordering::Relaxed);
+    }
+
+    Ok(added)
+}
+
+// Add paths to a completed index, skipping any outside the index roots or already present.
+// Returns how many entries were added.
+fn insert_index_entries(state: &IndexState, paths: &[PathBuf]) -> Result<usize, String> {
+    let is_complete = state.progress.lock().map(|p| p.is_complete).unwrap_or(false);
+    if !is_complete || paths.is_empty() {
+        return Ok(0);
+    }
+
+    let record_access_times = load_config().index_access_times;
+    let new_entries: Vec<IndexEntry> = {
+        let roots = state.roots.lock().map_err(|e| e.to_string())?;
+        paths.iter()
+            .filter_map(|path| {
+                let root = root_for_path(&roots, path)?;
+                let name = path.file_name()?.to_string_lossy().to_string();
+                let parent_folder = path.parent()
+                    .and_then(|p| p.file_name())
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_else(|| "~".to_string());
+                let mut entry = build_index_entry(path, &name, &parent_folder, record_access_times);
+                entry.root = root.path.to_string_lossy().to_string();
+                Some(entry)
+            })
+            .collect()
+    };
+
+    let mut entries = state.entries.write().map_err(|e| e.to_string())?;
+    let mut lower_names = state.lower_names.write().map_err(|e| e.to_string())?;
+    let mut existing: HashSet<String> = entries.iter().map(|e| e.path.clone()).collect();
+
+    let mut added = 0;
+    for entry in new_entries {
+        if existing.insert(entry.path.clone()) {
+            lower_names.push(entry.name.to_lowercase());
+            entries.push(entry);
+            added += 1;
+        }
+    }
+    if added > 0 {
+        state.unsaved_changes.store(true, Ordering::Relaxed);
+    }
+    Ok(added)
+}
+
+// Drop a path and everything beneath it from the index
+fn remove_index_entries(state: &IndexState, path: &Path) -> Result<(), String> {
+    let mut entries = state.entries.write().map_err(|e| e.to_string())?;
+    let mut lower_names = state.lower_names.write().map_err(|e| e.to_string())?;
+
+    let mut i = 0;
+    while i < entries.len() {
+        if Path::new(&entries[i].path).starts_with(path) {
+            entries.swap_remove(i);
+            lower_names.swap_remove(i);
+        } else {
+            i += 1;
+        }
+    }
+    state.unsaved_changes.store(true, Ordering::Relaxed);
+    Ok(())
+}
+
+// Point entries for `old` (and everything beneath it) at `new` after a rename or move.
+// Moving out of every index root drops the entries instead.
+fn rename_index_entries(state: &IndexState, old: &Path, new: &Path) -> Result<(), String> {
+    let new_root = {
+        let roots = state.roots.lock().map_err(|e| e.to_string())?;
+        root_for_path(&roots, new).map(|r| r.path.to_string_lossy().to_string())
+    };
+    let new_root = match new_root {
+        Some(root) => root,
+        None => return remove_index_entries(state, old),
+    };
+
+    let new_name = new.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let new_parent_folder = new.parent()
+        .and_then(|p| p.file_name())
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "~".to_string());
+
+    let mut entries = state.entries.write().map_err(|e| e.to_string())?;
+    let mut lower_names = state.lower_names.write().map_err(|e| e.to_string())?;
+
+    for (entry, lower_name) in entries.iter_mut().zip(lower_names.iter_mut()) {
+        let rest = match Path::new(&entry.path).strip_prefix(old) {
+            Ok(rest) => rest.to_path_buf(),
+            Err(_) => continue,
+        };
+        entry.root = new_root.clone();
+
+        if rest.as_os_str().is_empty() {
+            // The renamed entry itself
+            entry.name = new_name.clone();
+            entry.parent_folder = new_parent_folder.clone();
+            if !entry.is_directory {
+                entry.extension = entry_extension(&new_name);
+            }
+            *lower_name = new_name.to_lowercase();
+            entry.path = new.to_string_lossy().to_string();
+        } else {
+            // Direct children are named after their parent folder
+            if rest.components().count() == 1 {
+                entry.parent_folder = new_name.clone();
+            }
+            entry.path = new.join(rest).to_string_lossy().to_string();
+        }
+    }
+    state.unsaved_changes.store(true, Ordering::Relaxed);
+    Ok(())
+}
+
+#[tauri::command]
+fn create_directory(path: String, state: State<'_, IndexState>) -> Result<(), String> {
+    let target = normalize_lexically(Path::new(&path));
+    if target.exists() {
+        return Err(format!("Path already exists: {}", path));
+    }
+
+    // Every missing ancestor gets created too, and each becomes searchable
+    let mut created: Vec<PathBuf> = target.ancestors()
+        .take_while(|p| !p.exists())
+        .map(|p| p.to_path_buf())
+        .collect();
+
+    fs::create_dir_all(&target)
+        .map_err(|e| format!("Failed to create directory: {}", e))?;
+
+    created.reverse();
+    insert_index_entries(&state, &created)?;
+    Ok(())
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(tag = "kind", content = "message")]
+pub enum FileOpError {
+    IoError(String),
+    PermissionDenied(String),
+    AlreadyExists(String),
+    NotFound(String),
+    InvalidInput(String),
+}
+
+impl FileOpError {
+    fn from_io(context: &str, e: std::io::Error) -> Self {
+        let message = format!("{}: {}", context, e);
+        match e.kind() {
+            std::io::ErrorKind::PermissionDenied => FileOpError::PermissionDenied(message),
+            std::io::ErrorKind::AlreadyExists => FileOpError::AlreadyExists(message),
+            std::io::ErrorKind::NotFound => FileOpError::NotFound(message),
+            _ => FileOpError::IoError(message),
+        }
+    }
+}
+
+impl std::fmt::Display for FileOpError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FileOpError::IoError(message)
+            | FileOpError::PermissionDenied(message)
+            | FileOpError::AlreadyExists(message)
+            | FileOpError::NotFound(message)
+            | FileOpError::InvalidInput(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+// Lock poisoning and similar internal failures surface as plain I/O errors
+impl From<String> for FileOpError {
+    fn from(message: String) -> Self {
+        FileOpError::IoError(message)
+    }
+}
+
+// Copy a file or directory tree; symlinked directories are not followed, to avoid cycles.
+// Every created path is appended to `created`. With `on_bytes`, files are streamed and
+// the callback hears about each chunk written.
+fn copy_path_recursive(
+    src: &Path,
+    dest: &Path,
+    created: &mut Vec<PathBuf>,
+    mut on_bytes: Option<&mut dyn FnMut(u64)>,
+) -> Result<(), FileOpError> {
+    let metadata = fs::symlink_metadata(src)
+        .map_err(|e| FileOpError::from_io("Failed to read metadata", e))?;
+
+    if metadata.is_dir() {
+        fs::create_dir(dest).map_err(|e| FileOpError::from_io("Failed to create directory", e))?;
+        created.push(dest.to_path_buf());
+        let read_dir = fs::read_dir(src)
+            .map_err(|e| FileOpError::from_io("Failed to read directory", e))?;
+        for entry in read_dir.flatten() {
+            copy_path_recursive(&entry.path(), &dest.join(entry.file_name()), created, match on_bytes {
+                Some(ref mut on_bytes) => Some(&mut **on_bytes),
+                None => None,
+            })?;
+        }
+    } else if metadata.is_file() || src.is_file() {
+        let result = match on_bytes {
+            Some(on_bytes) => copy_file_streaming(src, dest, on_bytes),
+            None => fs::copy(src, dest).map(|_| ()),
+        };
+        result.map_err(|e| FileOpError::from_io("Failed to copy file", e))?;
+        created.push(dest.to_path_buf());
+    }
+    Ok(())
+}
+
+// fs::copy in 1 MB chunks, keeping permissions and modified time
+fn copy_file_streaming(src: &Path, dest: &Path, on_bytes: &mut dyn FnMut(u64)) -> std::io::Result<()> {
+    use std::io::{Read, Write};
+
+    let mut reader = fs::File::open(src)?;
+    let metadata = reader.metadata()?;
+    let mut writer = fs::File::create(dest)?;
+    let mut buffer = vec![0u8; 1024 * 1024];
+
+    loop {
+        let read = reader.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        writer.write_all(&buffer[..read])?;
+        on_bytes(read as u64);
+    }
+
+    writer.flush()?;
+    if let Ok(modified) = metadata.modified() {
+        let _ = writer.set_modified(modified);
+    }
+    fs::set_permissions(dest, metadata.permissions())?;
+    Ok(())
+}
+
+#[tauri::command]
+fn rename_entry(old_path: String, new_name: String, state: State<'_, IndexState>) -> Result<String, FileOpError> {
+    let old = PathBuf::from(&old_path);
+    let mut components = Path::new(&new_name).components();
+    let is_plain_name = matches!((components.next(), components.next()), (Some(Component::Normal(_)), None));
+    if !is_plain_name {
+        return Err(FileOpError::InvalidInput(format!("Not a valid file name: {}", new_name)));
+    }
+
+    let parent = old.parent()
+        .ok_or_else(|| FileOpError::InvalidInput(format!("Cannot rename a root: {}", old_path)))?;
+    let new = parent.join(&new_name);
+    fs::symlink_metadata(&old).map_err(|e| FileOpError::from_io("Failed to read source", e))?;
+    if fs::symlink_metadata(&new).is_ok() {
+        return Err(FileOpError::AlreadyExists(format!("Destination already exists: {}", new.display())));
+    }
+
+    fs::rename(&old, &new).map_err(|e| FileOpError::from_io("Failed to rename", e))?;
+    rename_index_entries(&state, &old, &new)?;
+    Ok(new.to_string_lossy().to_string())
+}
+
+// A deletion must name an entry inside its parent: roots and paths ending in ".." are refused
+fn validate_deletion_target(target: &Path, metadata: &fs::Metadata) -> Result<(), FileOpError> {
+    let name = match target.components().next_back() {
+        Some(Component::Normal(name)) => name,
+        _ => return Err(FileOpError::InvalidInput(format!("Not a file or folder: {}", target.display()))),
+    };
+    let parent = target.parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+
+    // Only a symlink's parent is resolved, since the link itself is what gets deleted
+    let untrusted = if metadata.file_type().is_symlink() { Path::new(".") } else { Path::new(name) };
+    resolve_within_base(parent, untrusted)
+        .map(|_| ())
+        .map_err(FileOpError::InvalidInput)
+}
+
+// The destination for copying or moving `source` into `dest_dir`, which must not exist yet.
+// Symlinks are resolved to validate it, but the path handed back is the one the caller gave.
+fn resolve_entry_destination(source: &Path, dest_dir: &Path) -> Result<PathBuf, FileOpError> {
+    let file_name = source.file_name()
+        .ok_or_else(|| FileOpError::InvalidInput(format!("Nothing to copy or move at: {}", source.display())))?;
+    if !dest_dir.is_dir() {
+        return Err(FileOpError::NotFound(format!("Destination is not a directory: {}", dest_dir.display())));
+    }
+    if fs::symlink_metadata(dest_dir.join(file_name)).is_ok() {
+        return Err(FileOpError::AlreadyExists(format!(
+            "Destination already exists: {}",
+            dest_dir.join(file_name).display()
+        )));
+    }
+
+    let resolved = resolve_within_base(dest_dir, Path::new(file_name)).map_err(FileOpError::InvalidInput)?;
+    // Copying or moving a directory into itself would recurse forever
+    let source_is_dir = fs::symlink_metadata(source).is_ok_and(|m| m.is_dir());
+    if source_is_dir && fs::canonicalize(source).is_ok_and(|s| resolved.starts_with(s)) {
+        return Err(FileOpError::InvalidInput("Cannot copy or move a directory into itself".to_string()));
+    }
+    Ok(dest_dir.join(file_name))
+}
+
+#[tauri::command]
+fn delete_entry(path: String, to_trash: bool, state: State<'_, IndexState>) -> Result<(), FileOpError> {
+    let target = PathBuf::from(&path);
+    let metadata = fs::symlink_metadata(&target)
+        .map_err(|e| FileOpError::from_io("Failed to read metadata", e))?;
+    validate_deletion_target(&target, &metadata)?;
+
+    if to_trash {
+        trash::delete(&target).map_err(|e| FileOpError::IoError(format!("Failed to move to trash: {}", e)))?;
+    } else if metadata.is_dir() {
+        fs::remove_dir_all(&target).map_err(|e| FileOpError::from_io("Failed to delete directory", e))?;
+    } else {
+        // Symlinks are removed themselves, never their targets
+        fs::remove_file(&target).map_err(|e| FileOpError::from_io("Failed to delete file", e))?;
+    }
+
+    remove_index_entries(&state, &target)?;
+    Ok(())
+}
+
+#[tauri::command]
+fn copy_entry(src: String, dest_dir: String, state: State<'_, IndexState>) -> Result<String, FileOpError> {
+    let source = PathBuf::from(&src);
+    let dest = resolve_entry_destination(&source, Path::new(&dest_dir))?;
+
+    let mut created = Vec::new();
+    let result = copy_path_recursive(&source, &dest, &mut created, None);
+    // Index whatever was copied, even if the copy stopped partway
+    insert_index_entries(&state, &created)?;
+    result?;
+
+    Ok(dest.to_string_lossy().to_string())
+}
+
+// Total bytes a move would have to copy. Fails on symlinked directories, which the copy
+// skips and the delete afterwards would lose.
+fn move_copy_size(path: &Path) -> Result<u64, String> {
+    let metadata = fs::symlink_metadata(path)
+        .map_err(|e| format!("Failed to read metadata: {}", e))?;
+
+    if metadata.is_dir() {
+        let read_dir = fs::read_dir(path)
+            .map_err(|e| format!("Failed to read directory: {}", e))?;
+        let mut total = 0;
+        for entry in read_dir.flatten() {
+            total += move_copy_size(&entry.path())?;
+        }
+        Ok(total)
+    } else if metadata.is_file() {
+        Ok(metadata.len())
+    } else if path.is_dir() {
+        Err(format!("Cannot move a symlinked directory across devices: {}", path.display()))
+    } else {
+        Ok(fs::metadata(path).map(|m| m.len()).unwrap_or(0))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MoveProgress {
+    pub src: String,
+    pub dest: String,
+    pub copied_bytes: u64,
+    pub total_bytes: u64,
+}
+
+// Async so a cross-device copy doesn't block the main thread
+#[tauri::command]
+async fn move_entry(
+    app: tauri::AppHandle,
+    src: String,
+    dest_dir: String,
+    state: State<'_, IndexState>,
+) -> Result<String, FileOpError> {
+    const PROGRESS_INTERVAL_BYTES: u64 = 8 * 1024 * 1024;
+
+    let source = PathBuf::from(&src);
+    let source_metadata = fs::symlink_metadata(&source)
+        .map_err(|e| FileOpError::from_io("Failed to read source", e))?;
+    let dest = resolve_entry_destination(&source, Path::new(&dest_dir))?;
+
+    match fs::rename(&source, &dest) {
+        Ok(()) => {
+            rename_index_entries(&state, &source, &dest)?;
+            return Ok(dest.to_string_lossy().to_string());
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::CrossesDevices => {}
+        Err(e) => return Err(FileOpError::from_io("Failed to move", e)),
+    }
+
+    // Different filesystems: copy everything over, then delete the source. Both can take a
+    // long time, so they run off the async runtime.
+    let (copy_source, copy_dest) = (source.clone(), dest.clone());
+    let copy_and_remove = move || -> Result<(Vec<PathBuf>, std::io::Result<()>), FileOpError> {
+        let total_bytes = move_copy_size(&copy_source)?;
+        let mut copied_bytes = 0;
+        let mut last_emitted = 0;
+        let emit_progress = |copied_bytes: u64| {
+            let _ = app.emit("move-progress", MoveProgress {
+                src: src.clone(),
+                dest: copy_dest.to_string_lossy().to_string(),
+                copied_bytes,
+                total_bytes,
+            });
+        };
+
+        let mut created = Vec::new();
+        let mut on_bytes = |bytes: u64| {
+            copied_bytes += bytes;
+            if copied_bytes - last_emitted >= PROGRESS_INTERVAL_BYTES {
+                last_emitted = copied_bytes;
+                emit_progress(copied_bytes);
+            }
+        };
+        if let Err(e) = copy_path_recursive(&copy_source, &copy_dest, &mut created, Some(&mut on_bytes)) {
+            // Leave the source untouched and clean up the partial copy
+            let _ = if copy_dest.is_dir() { fs::remove_dir_all(&copy_dest) } else { fs::remove_file(&copy_dest) };
+            return Err(e);
+        }
+        emit_progress(total_bytes);
+
+        let removed = if source_metadata.is_dir() {
+            fs::remove_dir_all(&copy_source)
+        } else {
+            fs::remove_file(&copy_source)
+        };
+        Ok((created, removed))
+    };
+    let (created, removed) = tauri::async_runtime::spawn_blocking(copy_and_remove)
+        .await
+        .map_err(|e| FileOpError::IoError(format!("Move failed: {}", e)))??;
+
+    // The copy exists either way, so the index should reflect it
+    remove_index_entries(&state, &source)?;
+    insert_index_entries(&state, &created)?;
+    removed.map_err(|e| FileOpError::from_io("Copied to destination but failed to remove source", e))?;
+
+    Ok(dest.to_string_lossy().to_string())
+}
+
+// Bookmarked folders that no longer exist are skipped rather than reported
+fn index_pinned_paths(state: &IndexState, refresh: bool) -> Result<usize, String> {
+    let mut added = 0;
+    for bookmark in load_bookmarks()? {
+        let path = PathBuf::from(&bookmark.path);
+        if path.is_dir() {
+            added += index_directory_shallow(&path, state, refresh)?;
+        }
+    }
+    Ok(added)
+}
+
+#[tauri::command]
+fn index_pinned_bookmarks(state: State<'_, IndexState>) -> Result<usize, String> {
+    index_pinned_paths(&state, false)
+}
+
+#[tauri::command]
+fn reindex_all_bookmarks(state: State<'_, IndexState>) -> Result<usize, String> {
+    index_pinned_paths(&state, true)
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Bookmark {
+    pub id: String,
+    pub path: String,
+    pub name: String,
+    #[serde(default)]
+    pub icon: Option<String>,
+}
+
+// A missing file is no bookmarks yet; an unreadable or corrupt one is an error, so it
+// doesn't get silently replaced by the next save
+fn load_bookmarks() -> Result<Vec<Bookmark>, String> {
+    let content = match fs::read_to_string(get_bookmarks_path()) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to read bookmarks: {}", e)),
+    };
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse bookmarks: {}", e))
+}
+
+fn save_bookmarks(bookmarks: &[Bookmark]) -> Result<(), String> {
+    fs::create_dir_all(get_config_dir())
+        .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    let content = serde_json::to_string_pretty(bookmarks)
+        .map_err(|e| format!("Failed to serialize bookmarks: {}", e))?;
+    write_file_atomic(&get_bookmarks_path(), content.as_bytes())
+        .map_err(|e| format!("Failed to write bookmarks: {}", e))
+}
+
+#[tauri::command]
+fn list_bookmarks() -> Result<Vec<Bookmark>, String> {
+    load_bookmarks()
+}
+
+#[tauri::command]
+fn add_bookmark(path: String, name: Option<String>, state: State<'_, IndexState>) -> Result<Bookmark, String> {
+    let target = normalize_lexically(Path::new(&path));
+    if !target.is_dir() {
+        return Err(format!("Not a directory: {}", path));
+    }
+
+    let mut bookmarks = load_bookmarks()?;
+    if bookmarks.iter().any(|b| normalize_lexically(Path::new(&b.path)) == target) {
+        return Err(format!("Already bookmarked: {}", target.display()));
+    }
+
+    // Nanosecond timestamps, bumped on the off chance two land on the same value
+    let mut nanos = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or(0);
+    while bookmarks.iter().any(|b| b.id == format!("{:x}", nanos)) {
+        nanos += 1;
+    }
+
+    let name = name
+        .map(|n| n.trim().to_string())
+        .filter(|n| !n.is_empty())
+        .or_else(|| target.file_name().map(|n| n.to_string_lossy().to_string()))
+        .unwrap_or_else(|| target.to_string_lossy().to_string());
+
+    let bookmark = Bookmark {
+        id: format!("{:x}", nanos),
+        path: target.to_string_lossy().to_string(),
+        name,
+        icon: None,
+    };
+    bookmarks.push(bookmark.clone());
+    save_bookmarks(&bookmarks)?;
+
+    // The bookmark is saved either way; a folder that can't be listed just isn't searchable yet
+    let _ = index_directory_shallow(&target, &state, false);
+    Ok(bookmark)
+}
+
+#[tauri::command]
+fn remove_bookmark(id: String) -> Result<(), String> {
+    let mut bookmarks = load_bookmarks()?;
+    let before = bookmarks.len();
+    bookmarks.retain(|b| b.id != id);
+    if bookmarks.len() == before {
+        return Err(format!("No bookmark with id: {}", id));
+    }
+    save_bookmarks(&bookmarks)
+}
+
+// Bookmarks missing from `ids` keep their relative order after the listed ones
+#[tauri::command]
+fn reorder_bookmarks(ids: Vec<String>) -> Result<(), String> {
+    let mut remaining = load_bookmarks()?;
+    let mut reordered = Vec::with_capacity(remaining.len());
+
+    for id in &ids {
+        let index = remaining.iter().position(|b| &b.id == id)
+            .ok_or_else(|| format!("No bookmark with id: {}", id))?;
+        reordered.push(remaining.remove(index));
+    }
+    reordered.extend(remaining);
+
+    save_bookmarks(&reordered)
+}
+
+fn index_directory(
+    path: &PathBuf,
+    entries: &mut Vec<IndexEntry>,
+    lower_names: &mut Vec<String>,
+    progress: &Arc<Mutex<IndexProgress>>,
+    skip_hidden: bool,
+    options: &IndexOptions,
+    cancel_requested: &Mutex<bool>,
+) {
+    if cancel_requested.lock().map(|c| *c).unwrap_or(false) {
+        return;
+    }
+
+    let dir_entries = match fs::read_dir(path) {
+        Ok(e) => e,
+        Err(_) => return,
+    };
+
+    let parent_folder = path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "~".to_string());
+
+    // Update current folder in progress
+    if let Ok(mut prog) = progress.lock() {
+        prog.current_folder = path.to_string_lossy().to_string();
+    }
+
+    let mut subdirs = Vec::new();
+
+    for entry in dir_entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+
+        // Skip hidden files/folders
+        if skip_hidden && name.starts_with('.') {
+            continue;
+        }
+
+        let file_path = entry.path();
+        if options.matches_exclude_pattern(&file_path) {
+            continue;
+        }
+
+        let index_entry = build_index_entry(&file_path, &name, &parent_folder, options.record_access_times);
+        let is_dir = index_entry.is_directory;
+
+        entries.push(index_entry);
+        lower_names.push(name.to_lowercase());
+
+        // Update total files count less frequently (every 100 files)
+        if entries.len().is_multiple_of(100) {
+            if let Ok(mut prog) = progress.lock() {
+                prog.total_files = entries.len();
+            }
+        }
+
+        if is_dir {
+            // Skip common large/unneeded directories
+            if !options.skips_directory(&name) {
+                if let Ok(mut prog) = progress.lock() {
+                    prog.total_folders += 1;
+                }
+                subdirs.push(file_path);
+            }
+        }
+    }
+
+    // Update indexed folders count
+    if let Ok(mut prog) = progress.lock() {
+        prog.indexed_folders += 1;
+        prog.total_files = entries.len();
+    }
+
+    // Recursively index subdirectories
+    for subdir in subdirs {
+        index_directory(&subdir, entries, lower_names, progress, skip_hidden, options, cancel_requested);
+    }
+}
+
+// Missing or non-directory roots are skipped, as are roots nested inside another root
+fn configured_index_roots(config: &Config, home: &Path) -> Vec<PathBuf> {
+    let mut roots: Vec<PathBuf> = config.index_roots.iter()
+        .flatten()
+        .map(|r| normalize_lexically(Path::new(r)))
+        .filter(|r| r.is_absolute() && r.is_dir())
+        .collect();
+    roots.sort_by_key(|r| r.components().count());
+
+    let mut distinct: Vec<PathBuf> = Vec::new();
+    for root in roots {
+        if !distinct.iter().any(|d| root.starts_with(d)) {
+            distinct.push(root);
+        }
+    }
+
+    if distinct.is_empty() {
+        distinct.push(home.to_path_buf());
+    }
+    distinct
+}
+
+// Copies walk progress into IndexState and emits it until the walk marks itself complete.
+// The completed event is left to the caller, once results are committed.
+fn spawn_progress_sync(app: tauri::AppHandle, progress: Arc<Mutex<IndexProgress>>) -> thread::JoinHandle<()> {
+    thread::spawn(move || {
+        loop {
+            thread::sleep(std::time::Duration::from_millis(200));
+            let sync_state: State<'_, IndexState> = app.state();
+
+            let is_done = {
+                if let Ok(prog) = progress.lock() {
+                    if let Ok(mut state_prog) = sync_state.progress.lock() {
+                        // Saves happen outside the walk, so keep the state's own timestamp
+                        let last_saved_at = state_prog.last_saved_at;
+                        *state_prog = prog.clone();
+                        state_prog.last_saved_at = last_saved_at;
+                    }
+                    if !prog.is_complete {
+                        let _ = app.emit("index-progress", &*prog);
+                    }
+                    prog.is_complete
+                } else {
+                    false
+                }
+            };
+
+            if is_done {
+                break;
+            }
+        }
+    })
+}
+
+#[tauri::command]
+fn start_indexing(app: tauri::AppHandle) -> Result<(), String> {
+    let state: State<'_, IndexState> = app.state();
+
+    // Check if already indexing
+    {
+        let is_indexing = state.is_indexing.lock().map_err(|e| e.to_string())?;
+        if *is_indexing {
+            return Ok(());
+        }
+    }
+
+    // Set indexing flag
+    {
+        let mut is_indexing = state.is_indexing.lock().map_err(|e| e.to_string())?;
+        *is_indexing = true;
+    }
+
+    // Get home directory
+    let home_dir = dirs::home_dir().ok_or("Could not find home directory")?;
+    let config = load_config();
+    let options = IndexOptions::from_config(&config, &home_dir);
+    let index_roots = configured_index_roots(&config, &home_dir);
+
+    // Initialize with the root folders, then increment as subfolders are discovered.
+    let total_folders = index_roots.len();
+    {
+        let mut progress = state.progress.lock().map_err(|e| e.to_string())?;
+        progress.total_folders = total_folders.max(1);
+    }
+
+    let app_handle = app.clone();
+
+    thread::spawn(move || {
+        let state: State<'_, IndexState> = app_handle.state();
+        let mut new_entries = Vec::new();
+        let mut new_lower_names = Vec::new();
+
+        // Use the state's progress directly wrapped in Arc for the indexing function
+        let progress_arc = Arc::new(Mutex::new(IndexProgress {
+            total_folders,
+            indexed_folders: 0,
+            total_files: 0,
+            current_folder: String::new(),
+            is_complete: false,
+            last_saved_at: None,
+        }));
+
+        let sync_handle = spawn_progress_sync(app_handle.clone(), Arc::clone(&progress_arc));
+
+        for root in &index_roots {
+            let start = new_entries.len();
+            index_directory(root, &mut new_entries, &mut new_lower_names, &progress_arc, true, &options, &state.cancel_requested);
+
+            let root_tag = root.to_string_lossy().to_string();
+            for entry in &mut new_entries[start..] {
+                entry.root = root_tag.clone();
+            }
+        }
+
+        // Included directories the root walks skipped get walked on their own, parents first
+        let mut includes: Vec<&PathBuf> = options.include_directories.iter().collect();
+        includes.sort_by_key(|p| p.components().count());
+        let mut walked_includes: Vec<&PathBuf> = Vec::new();
+        for include in includes {
+            let already_walked = walked_includes.iter().any(|w| include.starts_with(w));
+            let skipped_by_roots = index_roots.iter().all(|root| is_excluded_from_walk(root, include, &options));
+            if already_walked || !include.is_dir() || !skipped_by_roots {
+                continue;
+            }
+            walked_includes.push(include);
+            let start = new_entries.len();
+
+            let name = include.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+            let parent_folder = include.parent()
+                .and_then(|p| p.file_name())
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| "~".to_string());
+            new_entries.push(build_index_entry(include, &name, &parent_folder, options.record_access_times));
+            new_lower_names.push(name.to_lowercase());
+
+            index_directory(include, &mut new_entries, &mut new_lower_names, &progress_arc, true, &options, &state.cancel_requested);
+
+            // Tagged with the root it sits under, or itself when it's outside every root
+            let root_tag = index_roots.iter()
+                .filter(|root| include.starts_with(root))
+                .max_by_key(|root| root.components().count())
+                .unwrap_or(include)
+                .to_string_lossy()
+                .to_string();
+            for entry in &mut new_entries[start..] {
+                entry.root = root_tag.clone();
+            }
+        }
+
+        let total_files = new_entries.len();
 
         // Mark complete
         if let Ok(mut prog) = progress_arc.lock() {
@@ -359,191 +5095,1463 @@ fn start_indexing(app: tauri::AppHandle) -> Result<(), String> {
             prog.total_files = total_files;
         }
 
-        // Wait for sync thread to finish
-        let _ = sync_handle.join();
+        // Wait for sync thread to finish
+        let _ = sync_handle.join();
+
+        // Update the state with results
+        if let Ok(mut entries) = state.entries.write() {
+            *entries = new_entries;
+        }
+
+        if let Ok(mut lower_names) = state.lower_names.write() {
+            *lower_names = new_lower_names;
+        }
+
+        if let Ok(mut roots) = state.roots.lock() {
+            let indexed_at = now_secs();
+            *roots = index_roots.iter()
+                .map(|root| IndexRoot::new(root.clone(), indexed_at))
+                .collect();
+        }
+
+        if let Ok(mut progress) = state.progress.lock() {
+            progress.is_complete = true;
+            progress.total_files = total_files;
+            let _ = app_handle.emit("index-progress", &*progress);
+        }
+
+        // A cancelled run still commits and saves what it found, so partial search works
+        if let Ok(mut is_indexing) = state.is_indexing.lock() {
+            *is_indexing = false;
+        }
+        if let Ok(mut cancel_requested) = state.cancel_requested.lock() {
+            *cancel_requested = false;
+        }
+
+        // Save index to disk; a fresh save supersedes any JSON index left over
+        if save_index(&state).is_ok() {
+            let _ = fs::remove_file(get_legacy_index_path());
+        }
+
+        // The index itself is fine without a watch, so failing to start one is only reported
+        if load_config().watch_enabled {
+            if let Err(e) = start_watching(app_handle.clone()) {
+                let _ = app_handle.emit("index-watch-error", e);
+            }
+        }
+    });
+
+    Ok(())
+}
+
+// The subtree is walked first and swapped in afterwards, so searches keep finding the old
+// entries until the new ones are ready. A cancelled refresh leaves the old entries in place.
+#[tauri::command]
+async fn reindex_directory(app: tauri::AppHandle, path: String) -> Result<(), String> {
+    let state: State<'_, IndexState> = app.state();
+    let target = normalize_lexically(Path::new(&path));
+    if !target.is_dir() {
+        return Err(format!("Not a directory: {}", path));
+    }
+
+    let root_tag = {
+        let roots = state.roots.lock().map_err(|e| e.to_string())?;
+        root_for_path(&roots, &target)
+            .ok_or_else(|| format!("Path is outside the indexed folders: {}", path))?
+            .path.to_string_lossy().to_string()
+    };
+
+    {
+        let mut is_indexing = state.is_indexing.lock().map_err(|e| e.to_string())?;
+        if *is_indexing {
+            return Err("Indexing is already in progress".to_string());
+        }
+        *is_indexing = true;
+    }
+
+    // The walk and the swap can take a while on a large subtree, so they run off the async runtime
+    let walk_app = app.clone();
+    let refresh = move || {
+        let state: State<'_, IndexState> = walk_app.state();
+        let home = dirs::home_dir().unwrap_or_default();
+        let options = IndexOptions::from_config(&load_config(), &home);
+        let progress_arc = Arc::new(Mutex::new(IndexProgress {
+            total_folders: 1,
+            indexed_folders: 0,
+            total_files: 0,
+            current_folder: String::new(),
+            is_complete: false,
+            last_saved_at: None,
+        }));
+        let sync_handle = spawn_progress_sync(walk_app.clone(), Arc::clone(&progress_arc));
+
+        let mut new_entries = Vec::new();
+        let mut new_lower_names = Vec::new();
+        // Skipped directories are indexed themselves but never descended into
+        let walk = !target.file_name().is_some_and(|n| options.skips_directory(&n.to_string_lossy()));
+        if walk {
+            index_directory(&target, &mut new_entries, &mut new_lower_names, &progress_arc, true, &options, &state.cancel_requested);
+        }
+        for entry in &mut new_entries {
+            entry.root = root_tag.clone();
+        }
+
+        if let Ok(mut prog) = progress_arc.lock() {
+            prog.is_complete = true;
+        }
+        let _ = sync_handle.join();
+
+        let cancelled = state.cancel_requested.lock().map(|c| *c).unwrap_or(false);
+        if cancelled {
+            Ok(())
+        } else {
+            replace_subtree_entries(&state, &target, new_entries, new_lower_names)
+        }
+    };
+    let result = tauri::async_runtime::spawn_blocking(refresh)
+        .await
+        .map_err(|e| format!("Reindex failed: {}", e))
+        .and_then(|result| result);
+
+    if let Ok(mut progress) = state.progress.lock() {
+        progress.is_complete = true;
+        progress.total_files = state.entries.read().map(|e| e.len()).unwrap_or(0);
+        let _ = app.emit("index-progress", &*progress);
+    }
+    if let Ok(mut is_indexing) = state.is_indexing.lock() {
+        *is_indexing = false;
+    }
+    if let Ok(mut cancel_requested) = state.cancel_requested.lock() {
+        *cancel_requested = false;
+    }
+
+    result
+}
+
+// Everything strictly beneath `dir` is replaced; `dir`'s own entry and the rest stay as they are
+fn replace_subtree_entries(
+    state: &IndexState,
+    dir: &Path,
+    new_entries: Vec<IndexEntry>,
+    new_lower_names: Vec<String>,
+) -> Result<(), String> {
+    let mut entries = state.entries.write().map_err(|e| e.to_string())?;
+    let mut lower_names = state.lower_names.write().map_err(|e| e.to_string())?;
+
+    let mut i = 0;
+    while i < entries.len() {
+        let entry_path = Path::new(&entries[i].path);
+        if entry_path != dir && entry_path.starts_with(dir) {
+            entries.swap_remove(i);
+            lower_names.swap_remove(i);
+        } else {
+            i += 1;
+        }
+    }
+
+    entries.extend(new_entries);
+    lower_names.extend(new_lower_names);
+    state.unsaved_changes.store(true, Ordering::Relaxed);
+    Ok(())
+}
+
+// Returns false when there is no indexing run to cancel
+#[tauri::command]
+fn cancel_indexing(state: State<'_, IndexState>) -> Result<bool, String> {
+    let is_indexing = state.is_indexing.lock().map_err(|e| e.to_string())?;
+    if !*is_indexing {
+        return Ok(false);
+    }
+
+    *state.cancel_requested.lock().map_err(|e| e.to_string())? = true;
+    Ok(true)
+}
+
+#[tauri::command]
+fn get_index_progress(state: State<'_, IndexState>) -> IndexProgress {
+    state.progress.lock()
+        .map(|p| p.clone())
+        .unwrap_or_default()
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct RelevanceExplanation {
+    pub score: i32,
+    pub exact_match_bonus: i32,
+    pub starts_with_bonus: i32,
+    pub word_boundary_bonus: i32,
+    pub directory_bonus: i32,
+    pub length_bonus: i32,
+    pub priority_path_bonus: i32,
+    pub recency_bonus: i32,
+}
+
+// Lowercased query plus its word-boundary variants, computed once per search
+struct ScoringQuery {
+    lower: String,
+    dash: String,
+    underscore: String,
+}
+
+impl ScoringQuery {
+    fn new(query: &str) -> Self {
+        let lower = query.to_lowercase();
+        ScoringQuery {
+            dash: format!("-{}", lower),
+            underscore: format!("_{}", lower),
+            lower,
+        }
+    }
+}
+
+// Score a single entry, keeping each component so the ranking can be explained
+fn explain_relevance(entry: &IndexEntry, name_lower: &str, query: &ScoringQuery) -> RelevanceExplanation {
+    let mut explanation = RelevanceExplanation::default();
+
+    // Exact match gets highest score
+    if name_lower == query.lower {
+        explanation.exact_match_bonus = 1000;
+    }
+    // Starts with query gets high score
+    else if name_lower.starts_with(&query.lower) {
+        explanation.starts_with_bonus = 500;
+    }
+    // Query at word boundary (after - or _)
+    else if name_lower.contains(&query.dash)
+         || name_lower.contains(&query.underscore) {
+        explanation.word_boundary_bonus = 300;
+    }
+
+    // Directories get bonus
+    if entry.is_directory {
+        explanation.directory_bonus = 200;
+    }
+
+    // Shorter names rank higher (more relevant)
+    explanation.length_bonus = 50 - (entry.name.len() as i32).min(50);
+
+    // Files in projects folder get bonus
+    if entry.path.contains("/projects/") {
+        explanation.priority_path_bonus = 100;
+    }
+
+    // Search does not rank by recency yet, so recency_bonus stays 0
+    explanation.score = explanation.exact_match_bonus
+        + explanation.starts_with_bonus
+        + explanation.word_boundary_bonus
+        + explanation.directory_bonus
+        + explanation.length_bonus
+        + explanation.priority_path_bonus
+        + explanation.recency_bonus;
+
+    explanation
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SearchMode {
+    #[default]
+    Substring,
+    Fuzzy,
+    Regex,
+    Prefix,
+}
+
+// Every query character must appear in order. Consecutive runs earn more than scattered
+// matches, and matches at the start of a word earn a little extra.
+fn fuzzy_match_score(name_lower: &str, query_lower: &str) -> Option<i32> {
+    let mut query_chars = query_lower.chars().peekable();
+    let mut score = 0;
+    let mut run = 0;
+    let mut prev: Option<char> = None;
+
+    for c in name_lower.chars() {
+        match query_chars.peek() {
+            Some(&q) if q == c => {
+                query_chars.next();
+                run += 1;
+                score += 10 * run;
+                if prev.is_none_or(|p| matches!(p, '-' | '_' | '.' | ' ')) {
+                    score += 15;
+                }
+            }
+            Some(_) => run = 0,
+            None => break,
+        }
+        prev = Some(c);
+    }
+
+    query_chars.peek().is_none().then_some(score)
+}
+
+const MAX_SEARCH_HISTORY: usize = 100;
+
+fn record_search(state: &IndexState, query: &str, result_count: usize, latency: Duration) {
+    state.search_count.fetch_add(1, Ordering::Relaxed);
+    state.total_results.fetch_add(result_count as u64, Ordering::Relaxed);
+    state.total_latency_ns.fetch_add(latency.as_nanos() as u64, Ordering::Relaxed);
+    if result_count == 0 {
+        state.zero_result_count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    let query = query.trim();
+    if query.is_empty() {
+        return;
+    }
+    if let Ok(mut history) = state.search_history.lock() {
+        history.push(
+            SearchHistoryEntry { query: query.to_string(), searched_at: now_secs(), result_count },
+            search_history_limit(),
+        );
+        state.unsaved_search_history.store(true, Ordering::Relaxed);
+    }
+}
+
+#[tauri::command]
+fn search_index(
+    state: State<'_, IndexState>,
+    query: String,
+    search_mode: Option<SearchMode>,
+    extensions: Option<Vec<String>>,
+    parent_filter: Option<String>,
+) -> Vec<IndexEntry> {
+    if query.is_empty() {
+        return Vec::new();
+    }
+
+    let query = SearchQuery {
+        text: query,
+        search_mode: search_mode.unwrap_or_default(),
+        extensions: extensions.unwrap_or_default(),
+        parent_contains: parent_filter,
+        ..SearchQuery::default()
+    };
+    // Only a bad regex fails, and the plain search box has nowhere to show why
+    run_search_query(&state, &query)
+        .map(|result| result.entries)
+        .unwrap_or_default()
+}
+
+// Substring match anywhere in the full path. Matches in the entry's own name rank above
+// matches only in ancestor folders; within each group, later and shallower matches win.
+#[tauri::command]
+fn search_by_path(query: String, max_results: Option<usize>, state: State<'_, IndexState>) -> Vec<IndexEntry> {
+    let to_key = |s: &str| {
+        let lower = s.to_lowercase();
+        if cfg!(windows) { lower.replace('\\', "/") } else { lower }
+    };
+    let query = to_key(query.trim());
+    if query.is_empty() {
+        return Vec::new();
+    }
+
+    let entries = match state.entries.read() {
+        Ok(e) => e,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut scored: Vec<(i64, &IndexEntry)> = entries.iter()
+        .filter_map(|e| {
+            let path_lower = to_key(&e.path);
+            let last_match = path_lower.rfind(&query)?;
+            let name_start = path_lower.len().saturating_sub(e.name.len());
+
+            let mut score = 0i64;
+            if last_match + query.len() > name_start {
+                score += 1000;
+                if last_match == name_start {
+                    score += 200;
+                }
+            }
+            score += (last_match * 100 / path_lower.len().max(1)) as i64;
+            score -= Path::new(&e.path).components().count() as i64;
+            Some((score, e))
+        })
+        .collect();
+
+    scored.sort_by_key(|s| std::cmp::Reverse(s.0));
+    scored.into_iter()
+        .take(max_results.unwrap_or(100))
+        .map(|(_, e)| e.clone())
+        .collect()
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SearchSortBy {
+    #[default]
+    Relevance,
+    Name,
+    Modified,
+    Size,
+}
+
+// Every field is optional on the wire; unset ones take the defaults below
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct SearchQuery {
+    pub text: String,
+    pub search_mode: SearchMode,
+    // Older switches for search_mode; either one overrides it
+    pub fuzzy: bool,
+    pub use_regex: bool,
+    // Only entries under this directory
+    pub scope: Option<String>,
+    pub extensions: Vec<String>,
+    // Case-insensitive substrings of the parent folder's name and of the full path
+    pub parent_contains: Option<String>,
+    pub path_contains: Option<String>,
+    pub sort_by: SearchSortBy,
+    // 0 means the default of 100
+    #[serde(alias = "max_results")]
+    pub limit: usize,
+    pub offset: usize,
+    pub case_sensitive: bool,
+    pub only_directories: bool,
+    pub only_files: bool,
+    pub min_size_bytes: Option<u64>,
+    pub modified_after_secs: Option<u64>,
+    pub min_score: Option<i32>,
+}
+
+impl SearchQuery {
+    fn effective_mode(&self) -> SearchMode {
+        if self.use_regex {
+            SearchMode::Regex
+        } else if self.fuzzy {
+            SearchMode::Fuzzy
+        } else {
+            self.search_mode
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SearchResult {
+    pub entries: Vec<IndexEntry>,
+    pub total_matches: usize,
+    pub query_time_ms: u64,
+}
+
+// How a query's text is matched against names; returns a bonus added to the relevance score
+enum TextMatcher {
+    All,
+    Regex(regex::Regex),
+    Fuzzy(String),
+    Substring(String),
+    Prefix(String),
+}
+
+impl TextMatcher {
+    fn new(query: &SearchQuery) -> Result<Self, String> {
+        if query.text.is_empty() {
+            return Ok(TextMatcher::All);
+        }
+        let mode = query.effective_mode();
+        if mode == SearchMode::Regex {
+            return regex::RegexBuilder::new(&query.text)
+                .case_insensitive(!query.case_sensitive)
+                .size_limit(1 << 20)
+                .build()
+                .map(TextMatcher::Regex)
+                .map_err(|e| format!("Invalid regex: {}", e));
+        }
+        let text = if query.case_sensitive { query.text.clone() } else { query.text.to_lowercase() };
+        Ok(match mode {
+            SearchMode::Fuzzy => TextMatcher::Fuzzy(text),
+            SearchMode::Prefix => TextMatcher::Prefix(text),
+            _ => TextMatcher::Substring(text),
+        })
+    }
+
+    fn score(&self, name: &str) -> Option<i32> {
+        match self {
+            TextMatcher::All => Some(0),
+            TextMatcher::Regex(re) => re.is_match(name).then_some(0),
+            TextMatcher::Fuzzy(text) => fuzzy_match_score(name, text),
+            TextMatcher::Substring(text) => name.contains(text.as_str()).then_some(0),
+            TextMatcher::Prefix(text) => name.starts_with(text.as_str()).then_some(0),
+        }
+    }
+}
+
+#[tauri::command]
+fn search_index_advanced(query: SearchQuery, state: State<'_, IndexState>) -> Result<SearchResult, String> {
+    run_search_query(&state, &query)
+}
+
+// Matched case-insensitively against file names; the pattern is compiled once per call
+#[tauri::command]
+fn search_index_regex(pattern: String, max_results: Option<usize>, state: State<'_, IndexState>) -> Result<Vec<IndexEntry>, String> {
+    if pattern.is_empty() {
+        return Err("Enter a pattern to search for".to_string());
+    }
+
+    let query = SearchQuery {
+        text: pattern,
+        search_mode: SearchMode::Regex,
+        limit: max_results.unwrap_or(0),
+        ..SearchQuery::default()
+    };
+    run_search_query(&state, &query).map(|result| result.entries)
+}
+
+fn run_search_query(state: &IndexState, query: &SearchQuery) -> Result<SearchResult, String> {
+    const DEFAULT_LIMIT: usize = 100;
+    const MAX_LIMIT: usize = 1000;
+
+    let started = Instant::now();
+    let matcher = TextMatcher::new(query)?;
+    let scoring_query = ScoringQuery::new(&query.text);
+    let extensions: Vec<String> = query.extensions.iter()
+        .map(|e| e.trim_start_matches('.').to_lowercase())
+        .collect();
+    let scope = query.scope.as_ref().map(|s| normalize_lexically(Path::new(s)));
+    let lowered = |s: &Option<String>| s.as_ref().map(|s| s.to_lowercase()).filter(|s| !s.is_empty());
+    let parent_contains = lowered(&query.parent_contains);
+    let path_contains = lowered(&query.path_contains);
+    let limit = match query.limit {
+        0 => DEFAULT_LIMIT,
+        n => n.min(MAX_LIMIT),
+    };
+
+    let (entries_page, total_matches) = {
+        let entries = state.entries.read().map_err(|e| e.to_string())?;
+        let lower_names = state.lower_names.read().map_err(|e| e.to_string())?;
+        let use_lower = lower_names.len() == entries.len();
+
+        let mut matches: Vec<(i32, &IndexEntry)> = entries.iter()
+            .enumerate()
+            .filter(|(_, e)| !query.only_directories || e.is_directory)
+            .filter(|(_, e)| !query.only_files || !e.is_directory)
+            .filter(|(_, e)| scope.as_ref().is_none_or(|s| Path::new(&e.path).starts_with(s)))
+            .filter(|(_, e)| query.min_size_bytes.is_none_or(|min| e.size_bytes.is_some_and(|size| size >= min)))
+            .filter(|(_, e)| query.modified_after_secs.is_none_or(|after| e.modified_secs.is_some_and(|m| m >= after)))
+            .filter(|(_, e)| extensions.is_empty() || extensions.contains(&e.extension))
+            .filter(|(_, e)| parent_contains.as_ref().is_none_or(|p| e.parent_folder.to_lowercase().contains(p.as_str())))
+            .filter(|(_, e)| path_contains.as_ref().is_none_or(|p| e.path.to_lowercase().contains(p.as_str())))
+            .filter_map(|(idx, e)| {
+                let name_lower: std::borrow::Cow<str> = if use_lower {
+                    std::borrow::Cow::Borrowed(&lower_names[idx])
+                } else {
+                    std::borrow::Cow::Owned(e.name.to_lowercase())
+                };
+                let bonus = matcher.score(if query.case_sensitive { &e.name } else { &name_lower })?;
+                let score = explain_relevance(e, &name_lower, &scoring_query).score + bonus;
+                query.min_score.is_none_or(|min| score >= min).then_some((score, e))
+            })
+            .collect();
+
+        match query.sort_by {
+            SearchSortBy::Relevance => matches.sort_by_key(|m| std::cmp::Reverse(m.0)),
+            SearchSortBy::Name => matches.sort_by_cached_key(|m| m.1.name.to_lowercase()),
+            SearchSortBy::Modified => matches.sort_by_key(|m| std::cmp::Reverse(m.1.modified_secs)),
+            SearchSortBy::Size => matches.sort_by_key(|m| std::cmp::Reverse(m.1.size_bytes)),
+        }
+
+        let total = matches.len();
+        let page: Vec<IndexEntry> = matches.into_iter()
+            .skip(query.offset)
+            .take(limit)
+            .map(|(_, e)| e.clone())
+            .collect();
+        (page, total)
+    };
+
+    let elapsed = started.elapsed();
+    record_search(state, &query.text, total_matches, elapsed);
+
+    Ok(SearchResult {
+        entries: entries_page,
+        total_matches,
+        query_time_ms: elapsed.as_millis() as u64,
+    })
+}
+
+// "Search here": scores a one-off listing of `path` without touching the global index
+#[tauri::command]
+fn index_and_search(path: String, query: String) -> Result<Vec<IndexEntry>, String> {
+    const MAX_RESULTS: usize = 50;
+
+    let dir = PathBuf::from(&path);
+    if !dir.is_dir() {
+        return Err(format!("Not a directory: {}", path));
+    }
+    if query.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let scoring_query = ScoringQuery::new(&query);
+    let mut scored: Vec<(i32, IndexEntry)> = read_shallow_entries(&dir)?
+        .into_iter()
+        .filter_map(|e| {
+            let name_lower = e.name.to_lowercase();
+            if !name_lower.contains(&scoring_query.lower) {
+                return None;
+            }
+            Some((explain_relevance(&e, &name_lower, &scoring_query).score, e))
+        })
+        .collect();
+    scored.sort_by_key(|s| std::cmp::Reverse(s.0));
+
+    Ok(scored.into_iter()
+        .take(MAX_RESULTS)
+        .map(|(_, e)| e)
+        .collect())
+}
+
+#[tauri::command]
+fn search_index_by_path_segment(
+    segment: String,
+    dir_only: Option<bool>,
+    state: State<'_, IndexState>,
+) -> Vec<IndexEntry> {
+    if segment.is_empty() {
+        return Vec::new();
+    }
+
+    let entries = match state.entries.read() {
+        Ok(e) => e,
+        Err(_) => return Vec::new(),
+    };
+    let segment_lower = segment.to_lowercase();
+    let dir_only = dir_only.unwrap_or(false);
+
+    // Earlier matches rank higher, then shallower paths
+    let mut matches: Vec<(usize, usize, &IndexEntry)> = entries.iter()
+        .filter(|e| !dir_only || e.is_directory)
+        .filter_map(|e| {
+            let position = e.path.to_lowercase().find(&segment_lower)?;
+            Some((position, Path::new(&e.path).components().count(), e))
+        })
+        .collect();
+    matches.sort_by_key(|m| (m.0, m.1));
+
+    matches.into_iter()
+        .take(100)
+        .map(|(_, _, e)| e.clone())
+        .collect()
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SearchStats {
+    pub total_searches: u64,
+    pub avg_result_count: f64,
+    pub avg_latency_ms: f64,
+    pub most_common_query: String,
+    pub zero_result_queries: u64,
+}
+
+#[tauri::command]
+fn get_search_stats(state: State<'_, IndexState>) -> SearchStats {
+    let total_searches = state.search_count.load(Ordering::Relaxed);
+    let total_results = state.total_results.load(Ordering::Relaxed);
+    let total_latency_ns = state.total_latency_ns.load(Ordering::Relaxed);
+    let divisor = total_searches.max(1) as f64;
+
+    let most_common_query = state.search_history.lock()
+        .ok()
+        .and_then(|history| {
+            let mut counts: HashMap<&str, usize> = HashMap::new();
+            for entry in history.entries.iter() {
+                *counts.entry(entry.query.as_str()).or_default() += 1;
+            }
+            counts.into_iter()
+                .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(a.0)))
+                .map(|(query, _)| query.to_string())
+        })
+        .unwrap_or_default();
+
+    SearchStats {
+        total_searches,
+        avg_result_count: total_results as f64 / divisor,
+        avg_latency_ms: total_latency_ns as f64 / divisor / 1_000_000.0,
+        most_common_query,
+        zero_result_queries: state.zero_result_count.load(Ordering::Relaxed),
+    }
+}
+
+#[tauri::command]
+fn clear_search_stats(state: State<'_, IndexState>) {
+    state.search_count.store(0, Ordering::Relaxed);
+    state.total_results.store(0, Ordering::Relaxed);
+    state.total_latency_ns.store(0, Ordering::Relaxed);
+    state.zero_result_count.store(0, Ordering::Relaxed);
+}
+
+#[tauri::command]
+fn record_navigation(path: String) -> Result<(), String> {
+    let target = normalize_lexically(Path::new(&path));
+    if !target.is_dir() {
+        return Err(format!("Not a directory: {}", path));
+    }
+
+    let mut recent = RecentPaths::load();
+    recent.push(&target.to_string_lossy(), load_config().recent_paths_limit);
+    recent.save()
+}
+
+#[tauri::command]
+fn get_recent_paths() -> Vec<String> {
+    RecentPaths::load().paths.into_iter()
+        .filter(|p| Path::new(p).exists())
+        .collect()
+}
+
+#[tauri::command]
+fn clear_recent_paths() -> Result<(), String> {
+    RecentPaths::default().save()
+}
+
+#[tauri::command]
+fn get_search_history(state: State<'_, IndexState>) -> Vec<String> {
+    let limit = search_history_limit();
+    state.search_history.lock()
+        .map(|history| history.distinct_queries(limit))
+        .unwrap_or_default()
+}
+
+#[tauri::command]
+fn clear_search_history(state: State<'_, IndexState>) -> Result<(), String> {
+    state.search_history.lock().map_err(|e| e.to_string())?.entries.clear();
+    save_search_history(&state)
+}
+
+#[tauri::command]
+fn get_all_search_history_with_timestamps(state: State<'_, IndexState>) -> Vec<SearchHistoryEntry> {
+    state.search_history.lock()
+        .map(|history| history.entries.iter().cloned().collect())
+        .unwrap_or_default()
+}
+
+#[tauri::command]
+fn prune_search_history_before(timestamp: u64, state: State<'_, IndexState>) -> usize {
+    let pruned = match state.search_history.lock() {
+        Ok(mut history) => {
+            let before = history.entries.len();
+            history.entries.retain(|e| e.searched_at >= timestamp);
+            before - history.entries.len()
+        }
+        Err(_) => 0,
+    };
+    if pruned > 0 {
+        let _ = save_search_history(&state);
+    }
+    pruned
+}
+
+// Calendar days are UTC days
+#[tauri::command]
+fn get_search_history_for_date(date_secs: u64, state: State<'_, IndexState>) -> Vec<SearchHistoryEntry> {
+    const SECS_PER_DAY: u64 = 24 * 60 * 60;
+    let day = date_secs / SECS_PER_DAY;
+
+    state.search_history.lock()
+        .map(|history| {
+            history.entries.iter()
+                .filter(|e| e.searched_at / SECS_PER_DAY == day)
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[tauri::command]
+fn get_search_relevance_explanation(
+    query: String,
+    path: String,
+    state: State<'_, IndexState>,
+) -> Option<RelevanceExplanation> {
+    let entries = state.entries.read().ok()?;
+    let entry = entries.iter().find(|e| e.path == path)?;
+
+    Some(explain_relevance(entry, &entry.name.to_lowercase(), &ScoringQuery::new(&query)))
+}
+
+#[tauri::command]
+fn load_saved_index(state: State<'_, IndexState>) -> bool {
+    let msgpack = fs::read(get_index_path()).ok()
+        .and_then(|bytes| rmp_serde::from_slice::<Vec<IndexEntry>>(&bytes).ok());
+    // Not migrated yet, or the msgpack file is unreadable: fall back to the JSON index
+    let (index_path, loaded) = match msgpack {
+        Some(entries) => (get_index_path(), Some(entries)),
+        None => (get_legacy_index_path(), read_legacy_index_file().ok()),
+    };
+
+    if let Some(mut entries) = loaded {
+        // Indexes saved before extensions were stored need them filled in
+        for entry in entries.iter_mut().filter(|e| !e.is_directory && e.extension.is_empty()) {
+            entry.extension = entry_extension(&entry.name);
+        }
+        let lower_names = entries.iter().map(|e| e.name.to_lowercase()).collect::<Vec<_>>();
+        if let Ok(mut state_entries) = state.entries.write() {
+            let count = entries.len();
+            *state_entries = entries;
+            if let Ok(mut state_lower_names) = state.lower_names.write() {
+                *state_lower_names = lower_names;
+            }
+
+            // The index file is written right after it's built
+            let indexed_at = fs::metadata(&index_path).ok()
+                .and_then(|m| m.modified().ok())
+                .and_then(system_time_secs)
+                .unwrap_or_else(now_secs);
+            // Entries saved before roots were tagged all came from the home directory
+            let mut root_paths: Vec<PathBuf> = state_entries.iter()
+                .map(|e| e.root.as_str())
+                .collect::<HashSet<&str>>()
+                .into_iter()
+                .filter_map(|r| if r.is_empty() { dirs::home_dir() } else { Some(PathBuf::from(r)) })
+                .collect();
+            root_paths.sort();
+            root_paths.dedup();
+            if let Ok(mut roots) = state.roots.lock() {
+                *roots = root_paths.into_iter()
+                    .map(|root| IndexRoot::new(root, indexed_at))
+                    .collect();
+            }
+
+            // Update progress to show loaded state
+            if let Ok(mut progress) = state.progress.lock() {
+                progress.total_files = count;
+                progress.is_complete = true;
+                progress.last_saved_at = Some(indexed_at);
+            }
+            return true;
+        }
+    }
+    false
+}
+
+// The JSON file is only removed once the MessagePack copy is safely written
+#[tauri::command]
+fn migrate_index_format() -> Result<(), String> {
+    if !get_legacy_index_path().exists() {
+        return Err("No JSON index to migrate".to_string());
+    }
+
+    let entries = read_legacy_index_file()?;
+    write_index_file(&entries)?;
+    fs::remove_file(get_legacy_index_path())
+        .map_err(|e| format!("Failed to remove JSON index: {}", e))
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ExtensionConflict {
+    pub path: String,
+    pub claimed_extension: String,
+    pub detected_type: String,
+}
+
+fn mime_from_extension(extension: &str) -> Option<&'static str> {
+    let mime = match extension.to_lowercase().as_str() {
+        "jpg" | "jpeg" => "image/jpeg",
+        "png" => "image/png",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "bmp" => "image/bmp",
+        "tif" | "tiff" => "image/tiff",
+        "heic" => "image/heic",
+        "ico" => "image/vnd.microsoft.icon",
+        "svg" => "image/svg+xml",
+        "pdf" => "application/pdf",
+        "zip" => "application/zip",
+        "gz" => "application/gzip",
+        "tar" => "application/x-tar",
+        "7z" => "application/x-7z-compressed",
+        "rar" => "application/vnd.rar",
+        "mp3" => "audio/mpeg",
+        "wav" => "audio/x-wav",
+        "flac" => "audio/x-flac",
+        "mp4" => "video/mp4",
+        "mov" => "video/quicktime",
+        "mkv" => "video/x-matroska",
+        "webm" => "video/webm",
+        "avi" => "video/x-msvideo",
+        "json" => "application/json",
+        "txt" | "md" | "log" => "text/plain",
+        "html" | "htm" => "text/html",
+        "css" => "text/css",
+        "js" | "mjs" => "text/javascript",
+        "ts" | "tsx" | "rs" | "py" | "go" | "java" | "c" | "h" | "cpp" | "sh" | "toml" | "yaml" | "yml" => "text/plain",
+        "xml" => "text/xml",
+        "csv" => "text/csv",
+        _ => return None,
+    };
+    Some(mime)
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FileInfo {
+    pub name: String,
+    pub path: String,
+    pub is_directory: bool,
+    // 0 for directories
+    pub size_bytes: u64,
+    pub created_secs: Option<u64>,
+    pub modified_secs: Option<u64>,
+    pub accessed_secs: Option<u64>,
+    pub permissions: String,
+    pub is_symlink: bool,
+    pub symlink_target: Option<String>,
+    // Looked up from the extension; the contents are not sniffed
+    pub mime_type: Option<String>,
+    pub is_hidden: bool,
+}
+
+#[cfg(unix)]
+fn permissions_summary(metadata: &fs::Metadata) -> String {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mode = metadata.permissions().mode();
+    let flags = ['r', 'w', 'x'];
+    (0..9)
+        .map(|i| if mode & (0o400 >> i) != 0 { flags[i % 3] } else { '-' })
+        .collect()
+}
+
+// Reading full ACLs needs the security APIs, so this summarises the attribute bits instead
+#[cfg(windows)]
+fn permissions_summary(metadata: &fs::Metadata) -> String {
+    use std::os::windows::fs::MetadataExt;
+    const FILE_ATTRIBUTE_SYSTEM: u32 = 0x4;
 
-        // Update the state with results
-        if let Ok(mut entries) = state.entries.lock() {
-            *entries = new_entries;
-        }
+    let mut summary = if metadata.permissions().readonly() { "read-only" } else { "read-write" }.to_string();
+    if metadata.file_attributes() & FILE_ATTRIBUTE_SYSTEM != 0 {
+        summary.push_str(", system");
+    }
+    summary
+}
 
-        if let Ok(mut lower_names) = state.lower_names.lock() {
-            *lower_names = new_lower_names;
-        }
+#[cfg(windows)]
+fn has_hidden_attribute(metadata: &fs::Metadata) -> bool {
+    use std::os::windows::fs::MetadataExt;
+    const FILE_ATTRIBUTE_HIDDEN: u32 = 0x2;
+    metadata.file_attributes() & FILE_ATTRIBUTE_HIDDEN != 0
+}
 
-        if let Ok(mut progress) = state.progress.lock() {
-            progress.is_complete = true;
-            progress.total_files = total_files;
-        }
+#[cfg(not(windows))]
+fn has_hidden_attribute(_metadata: &fs::Metadata) -> bool {
+    false
+}
 
-        if let Ok(mut is_indexing) = state.is_indexing.lock() {
-            *is_indexing = false;
-        }
+#[tauri::command]
+fn get_file_info(path: String) -> Result<FileInfo, String> {
+    let target = PathBuf::from(&path);
+    let link_metadata = fs::symlink_metadata(&target)
+        .map_err(|e| format!("Failed to read metadata: {}", e))?;
+    let is_symlink = link_metadata.file_type().is_symlink();
+    // Describe what a symlink points at, unless it dangles
+    let metadata = if is_symlink {
+        fs::metadata(&target).unwrap_or_else(|_| link_metadata.clone())
+    } else {
+        link_metadata.clone()
+    };
 
-        // Save index to disk
-        let index_path = get_index_path();
-        if let Ok(entries) = state.entries.lock() {
-            if let Ok(content) = serde_json::to_string(&*entries) {
-                let _ = fs::create_dir_all(get_config_dir());
-                let _ = fs::write(index_path, content);
-            }
-        };
-    });
+    let name = target.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.clone());
+    let is_directory = metadata.is_dir();
 
-    Ok(())
+    Ok(FileInfo {
+        is_hidden: name.starts_with('.') || has_hidden_attribute(&link_metadata),
+        mime_type: if is_directory {
+            None
+        } else {
+            mime_from_extension(&entry_extension(&name)).map(|m| m.to_string())
+        },
+        name,
+        path: target.to_string_lossy().to_string(),
+        is_directory,
+        size_bytes: if metadata.is_file() { metadata.len() } else { 0 },
+        created_secs: metadata.created().ok().and_then(system_time_secs),
+        modified_secs: metadata.modified().ok().and_then(system_time_secs),
+        accessed_secs: metadata.accessed().ok().and_then(system_time_secs),
+        permissions: permissions_summary(&metadata),
+        is_symlink,
+        symlink_target: if is_symlink {
+            fs::read_link(&target).ok().map(|t| t.to_string_lossy().to_string())
+        } else {
+            None
+        },
+    })
+}
+
+// Sniff the real type from magic bytes; plain-text files that parse as JSON count as JSON
+fn detect_mime_type(path: &Path, claimed_mime: &str) -> Option<String> {
+    if let Ok(Some(kind)) = infer::get_from_path(path) {
+        return Some(kind.mime_type().to_string());
+    }
+
+    if claimed_mime == "text/plain" {
+        let metadata = fs::metadata(path).ok()?;
+        if metadata.len() <= 1024 * 1024 {
+            let bytes = fs::read(path).ok()?;
+            let trimmed = String::from_utf8_lossy(&bytes);
+            let trimmed = trimmed.trim_start();
+            if (trimmed.starts_with('{') || trimmed.starts_with('['))
+                && serde_json::from_slice::<serde_json::Value>(&bytes).is_ok()
+            {
+                return Some("application/json".to_string());
+            }
+        }
+    }
+    None
 }
 
 #[tauri::command]
-fn get_index_progress(state: State<'_, IndexState>) -> IndexProgress {
-    state.progress.lock()
-        .map(|p| p.clone())
-        .unwrap_or_default()
+fn get_entries_with_extension_change(state: State<'_, IndexState>) -> Vec<ExtensionConflict> {
+    const SAMPLE_SIZE: usize = 500;
+    const LIKELY_MISLABELED: [&str; 6] = ["/Pictures/", "/Photos/", "/DCIM/", "/projects/", "/src/", "/Downloads/"];
+
+    // Pick candidates under the lock, then do the file IO without holding it
+    let mut candidates: Vec<(bool, String, String)> = match state.entries.read() {
+        Ok(entries) => entries.iter()
+            .filter(|e| !e.is_directory)
+            .filter_map(|e| {
+                mime_from_extension(&e.extension)?;
+                let likely = LIKELY_MISLABELED.iter().any(|dir| e.path.contains(dir));
+                Some((likely, e.path.clone(), e.extension.clone()))
+            })
+            .collect(),
+        Err(_) => return Vec::new(),
+    };
+    // Stable sort keeps index order within each group
+    candidates.sort_by_key(|c| !c.0);
+
+    candidates.into_iter()
+        .take(SAMPLE_SIZE)
+        .filter_map(|(_, path, extension)| {
+            let claimed_mime = mime_from_extension(&extension)?;
+            let detected = detect_mime_type(Path::new(&path), claimed_mime)?;
+            (detected != claimed_mime).then_some(ExtensionConflict {
+                path,
+                claimed_extension: extension,
+                detected_type: detected,
+            })
+        })
+        .collect()
 }
 
 #[tauri::command]
-fn search_index(state: State<'_, IndexState>, query: String) -> Vec<IndexEntry> {
-    let entries = match state.entries.lock() {
+fn get_stale_index_entries(state: State<'_, IndexState>) -> Vec<IndexEntry> {
+    let entries = match state.entries.read() {
         Ok(e) => e,
         Err(_) => return Vec::new(),
     };
-    let lower_names = match state.lower_names.lock() {
-        Ok(n) => n,
-        Err(_) => return Vec::new(),
+
+    // Only check entries untouched for a day; recently modified paths are unlikely to be gone
+    let cutoff = now_secs().saturating_sub(24 * 60 * 60);
+
+    entries.iter()
+        .filter(|e| e.modified_secs.is_some_and(|m| m < cutoff))
+        .filter(|e| !PathBuf::from(&e.path).exists())
+        .take(500)
+        .cloned()
+        .collect()
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct IntegrityReport {
+    pub total_checked: usize,
+    pub missing_count: usize,
+    // At most MAX_REPORTED_MISSING_ENTRIES; missing_count has the full number
+    pub missing_entries: Vec<IndexEntry>,
+}
+
+const MAX_REPORTED_MISSING_ENTRIES: usize = 1000;
+
+// Paths are copied out first so the index isn't locked while every entry hits the disk.
+// Broken symlinks still exist, so they aren't reported.
+fn find_missing_index_paths(state: &IndexState) -> Result<(usize, HashSet<String>), String> {
+    use rayon::prelude::*;
+
+    let paths: Vec<String> = state.entries.read()
+        .map_err(|e| e.to_string())?
+        .iter()
+        .map(|e| e.path.clone())
+        .collect();
+    let missing = paths.par_iter()
+        .filter(|path| fs::symlink_metadata(path).is_err())
+        .cloned()
+        .collect();
+    Ok((paths.len(), missing))
+}
+
+#[tauri::command]
+async fn check_index_integrity(app: tauri::AppHandle) -> Result<IntegrityReport, String> {
+    let check = move || {
+        let state: State<'_, IndexState> = app.state();
+        let (total_checked, missing) = find_missing_index_paths(&state)?;
+        let entries = state.entries.read().map_err(|e| e.to_string())?;
+        let missing_entries = entries.iter()
+            .filter(|e| missing.contains(&e.path))
+            .take(MAX_REPORTED_MISSING_ENTRIES)
+            .cloned()
+            .collect();
+        Ok(IntegrityReport {
+            total_checked,
+            missing_count: missing.len(),
+            missing_entries,
+        })
     };
 
-    if query.is_empty() {
-        return Vec::new();
-    }
+    tauri::async_runtime::spawn_blocking(check)
+        .await
+        .map_err(|e| format!("Integrity check failed: {}", e))?
+}
 
-    let query_lower = query.to_lowercase();
-    let query_dash = format!("-{}", query_lower);
-    let query_underscore = format!("_{}", query_lower);
-    let use_lower = lower_names.len() == entries.len();
+// Re-checks every entry rather than trusting an earlier report, then saves the result
+#[tauri::command]
+async fn repair_index(app: tauri::AppHandle) -> Result<usize, String> {
+    let repair = move || {
+        let state: State<'_, IndexState> = app.state();
+        if state.is_indexing.lock().map(|i| *i).unwrap_or(false) {
+            return Err("Indexing is in progress".to_string());
+        }
+        let (_, missing) = find_missing_index_paths(&state)?;
+        if missing.is_empty() {
+            return Ok(0);
+        }
 
-    // Collect matching entries with a score
-    let mut scored: Vec<(i32, &IndexEntry)> = Vec::new();
-    if use_lower {
-        for (idx, e) in entries.iter().enumerate() {
-            let name_lower = &lower_names[idx];
-            if !name_lower.contains(&query_lower) {
-                continue;
+        let removed = {
+            let mut entries = state.entries.write().map_err(|e| e.to_string())?;
+            let mut lower_names = state.lower_names.write().map_err(|e| e.to_string())?;
+            let before = entries.len();
+            let mut i = 0;
+            while i < entries.len() {
+                if missing.contains(&entries[i].path) {
+                    entries.swap_remove(i);
+                    lower_names.swap_remove(i);
+                } else {
+                    i += 1;
+                }
             }
-            let mut score = 0;
+            before - entries.len()
+        };
+        if let Ok(mut progress) = state.progress.lock() {
+            progress.total_files = progress.total_files.saturating_sub(removed);
+        }
 
-            // Exact match gets highest score
-            if name_lower == &query_lower {
-                score += 1000;
-            }
-            // Starts with query gets high score
-            else if name_lower.starts_with(&query_lower) {
-                score += 500;
-            }
-            // Query at word boundary (after - or _)
-            else if name_lower.contains(&query_dash)
-                 || name_lower.contains(&query_underscore) {
-                score += 300;
-            }
+        save_index(&state)?;
+        Ok(removed)
+    };
 
-            // Directories get bonus
-            if e.is_directory {
-                score += 200;
-            }
+    tauri::async_runtime::spawn_blocking(repair)
+        .await
+        .map_err(|e| format!("Index repair failed: {}", e))?
+}
 
-            // Shorter names rank higher (more relevant)
-            score += 50 - (e.name.len() as i32).min(50);
+// 0.0 is a fresh index, 1.0 a very stale one.
+// Checking every entry for existence is too slow here, so missing entries are estimated from a sample.
+#[tauri::command]
+fn get_index_staleness_score(state: State<'_, IndexState>, watcher: State<'_, WatcherState>) -> f64 {
+    const STALE_AFTER_SECS: f64 = 7.0 * 24.0 * 60.0 * 60.0;
+    const SAMPLE_SIZE: usize = 200;
 
-            // Files in projects folder get bonus
-            if e.path.contains("/projects/") {
-                score += 100;
-            }
+    // The oldest root decides
+    let indexed_at = match state.roots.lock().ok().and_then(|r| r.iter().map(|r| r.indexed_at).min()) {
+        Some(t) => t,
+        None => return 1.0,
+    };
+    // Copy out what's needed so searches aren't blocked while the sample hits the disk
+    let (sampled, dir_ratio) = match state.entries.read() {
+        Ok(entries) if !entries.is_empty() => {
+            let step = (entries.len() / SAMPLE_SIZE).max(1);
+            let sampled: Vec<String> = entries.iter()
+                .step_by(step)
+                .take(SAMPLE_SIZE)
+                .map(|e| e.path.clone())
+                .collect();
+            let dirs = entries.iter().filter(|e| e.is_directory).count();
+            (sampled, dirs as f64 / entries.len() as f64)
+        }
+        _ => return 1.0,
+    };
+
+    let age_score = (now_secs().saturating_sub(indexed_at) as f64 / STALE_AFTER_SECS).min(1.0);
+
+    let missing = sampled.iter().filter(|p| !Path::new(p).exists()).count();
+    let missing_score = missing as f64 / sampled.len() as f64;
+
+    // Homes usually sit well under a third directories; more suggests files were deleted from under them
+    let dir_score = ((dir_ratio - 0.3) / 0.7).clamp(0.0, 1.0);
+
+    // Age matters less while a watcher keeps the index updated
+    let watching = watcher.watches.lock().map(|w| !w.is_empty()).unwrap_or(false);
+    let age_weight = if watching { 0.2 } else { 0.5 };
+
+    (age_weight * age_score + 0.4 * missing_score + 0.1 * dir_score).min(1.0)
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct IndexCoverage {
+    pub checked_paths: usize,
+    pub in_index: usize,
+    pub missing_from_index: Vec<String>,
+    pub coverage_percent: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FolderNameCount {
+    pub folder_name: String,
+    pub count: usize,
+}
+
+// Keyed on the parent's name, so every "src" directory is counted together
+#[tauri::command]
+fn get_recently_indexed_count_by_folder(state: State<'_, IndexState>) -> Vec<FolderNameCount> {
+    const MAX_FOLDERS: usize = 500;
 
-            scored.push((score, e));
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    if let Ok(entries) = state.entries.read() {
+        for entry in entries.iter() {
+            *counts.entry(entry.parent_folder.clone()).or_insert(0) += 1;
         }
-    } else {
-        for e in entries.iter() {
-            let name_lower = e.name.to_lowercase();
-            if !name_lower.contains(&query_lower) {
-                continue;
-            }
-            let mut score = 0;
+    }
 
-            if name_lower == query_lower {
-                score += 1000;
-            } else if name_lower.starts_with(&query_lower) {
-                score += 500;
-            } else if name_lower.contains(&query_dash)
-                || name_lower.contains(&query_underscore)
-            {
-                score += 300;
-            }
+    let mut counts: Vec<FolderNameCount> = counts.into_iter()
+        .map(|(folder_name, count)| FolderNameCount { folder_name, count })
+        .collect();
+    counts.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.folder_name.cmp(&b.folder_name)));
+    counts.truncate(MAX_FOLDERS);
+    counts
+}
 
-            if e.is_directory {
-                score += 200;
-            }
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DirectoryStat {
+    pub path: String,
+    pub count: usize,
+}
 
-            score += 50 - (e.name.len() as i32).min(50);
+// Keyed on the full parent path, so same-named directories in different projects stay apart
+#[tauri::command]
+fn get_largest_directories_by_file_count(n: usize, state: State<'_, IndexState>) -> Vec<DirectoryStat> {
+    const MAX_DIRECTORIES: usize = 50;
 
-            if e.path.contains("/projects/") {
-                score += 100;
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    if let Ok(entries) = state.entries.read() {
+        for entry in entries.iter() {
+            if let Some(parent) = Path::new(&entry.path).parent() {
+                *counts.entry(parent.to_string_lossy().to_string()).or_insert(0) += 1;
             }
+        }
+    }
+
+    let mut stats: Vec<DirectoryStat> = counts.into_iter()
+        .map(|(path, count)| DirectoryStat { path, count })
+        .collect();
+    stats.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.path.cmp(&b.path)));
+    stats.truncate(n.min(MAX_DIRECTORIES));
+    stats
+}
 
-            scored.push((score, e));
+#[tauri::command]
+fn get_index_coverage(paths: Vec<String>, state: State<'_, IndexState>) -> IndexCoverage {
+    let missing_from_index: Vec<String> = match state.entries.read() {
+        Ok(entries) => {
+            let indexed: HashSet<&str> = entries.iter().map(|e| e.path.as_str()).collect();
+            paths.iter()
+                .filter(|p| !indexed.contains(p.as_str()))
+                .cloned()
+                .collect()
         }
+        Err(_) => paths.clone(),
+    };
+
+    let checked_paths = paths.len();
+    let in_index = checked_paths - missing_from_index.len();
+    let coverage_percent = if checked_paths == 0 {
+        0.0
+    } else {
+        in_index as f64 / checked_paths as f64 * 100.0
+    };
+
+    IndexCoverage {
+        checked_paths,
+        in_index,
+        missing_from_index,
+        coverage_percent,
     }
+}
 
-    // Sort by score descending
-    scored.sort_by(|a, b| b.0.cmp(&a.0));
+#[tauri::command]
+fn get_entries_added_since_timestamp(timestamp: u64, state: State<'_, IndexState>) -> Vec<IndexEntry> {
+    match state.entries.read() {
+        Ok(entries) => entries.iter()
+            .filter(|e| e.indexed_at >= timestamp)
+            .cloned()
+            .collect(),
+        Err(_) => Vec::new(),
+    }
+}
 
-    // Return top 100
-    scored.into_iter()
-        .take(100)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NeighborResult {
+    pub before: Vec<IndexEntry>,
+    pub after: Vec<IndexEntry>,
+    pub current_position: usize,
+    pub total_siblings: usize,
+}
+
+// Siblings share the entry's parent directory; parent_folder alone would merge every "src"
+#[tauri::command]
+fn get_entry_neighbors(path: String, radius: usize, state: State<'_, IndexState>) -> NeighborResult {
+    const MAX_RADIUS: usize = 50;
+
+    let empty = NeighborResult { before: Vec::new(), after: Vec::new(), current_position: 0, total_siblings: 0 };
+    let parent = match Path::new(&path).parent() {
+        Some(p) => p,
+        None => return empty,
+    };
+    let entries = match state.entries.read() {
+        Ok(e) => e,
+        Err(_) => return empty,
+    };
+
+    let mut siblings: Vec<&IndexEntry> = entries.iter()
+        .filter(|e| Path::new(&e.path).parent() == Some(parent))
+        .collect();
+    siblings.sort_by_cached_key(|e| (e.name.to_lowercase(), e.name.clone()));
+
+    let position = match siblings.iter().position(|e| e.path == path) {
+        Some(p) => p,
+        None => return empty,
+    };
+    let radius = radius.min(MAX_RADIUS);
+
+    NeighborResult {
+        before: siblings[position.saturating_sub(radius)..position].iter().map(|e| (*e).clone()).collect(),
+        after: siblings.iter().skip(position + 1).take(radius).map(|e| (*e).clone()).collect(),
+        current_position: position,
+        total_siblings: siblings.len(),
+    }
+}
+
+// Files whose timestamp (picked by `timestamp`) falls in [start_secs, end_secs], newest first
+fn entries_in_time_range(
+    state: &IndexState,
+    start_secs: u64,
+    end_secs: u64,
+    timestamp: fn(&IndexEntry) -> Option<u64>,
+) -> Result<Vec<IndexEntry>, String> {
+    if start_secs > end_secs {
+        return Err("Start time must not be after end time".to_string());
+    }
+    let latest_plausible = now_secs() + 24 * 60 * 60;
+    if start_secs == 0 || end_secs > latest_plausible {
+        return Err("Time range is not a plausible Unix timestamp range".to_string());
+    }
+
+    let entries = state.entries.read().map_err(|e| e.to_string())?;
+    let mut matches: Vec<(u64, &IndexEntry)> = entries.iter()
+        .filter(|e| !e.is_directory)
+        .filter_map(|e| {
+            let secs = timestamp(e)?;
+            (start_secs..=end_secs).contains(&secs).then_some((secs, e))
+        })
+        .collect();
+    matches.sort_by_key(|m| std::cmp::Reverse(m.0));
+
+    Ok(matches.into_iter()
+        .take(1000)
         .map(|(_, e)| e.clone())
-        .collect()
+        .collect())
 }
 
 #[tauri::command]
-fn load_saved_index(state: State<'_, IndexState>) -> bool {
-    let index_path = get_index_path();
-
-    if !index_path.exists() {
-        return false;
-    }
-
-    match fs::read_to_string(&index_path) {
-        Ok(content) => {
-            match serde_json::from_str::<Vec<IndexEntry>>(&content) {
-                Ok(entries) => {
-                    let lower_names = entries.iter().map(|e| e.name.to_lowercase()).collect::<Vec<_>>();
-                    if let Ok(mut state_entries) = state.entries.lock() {
-                        let count = entries.len();
-                        *state_entries = entries;
-                        if let Ok(mut state_lower_names) = state.lower_names.lock() {
-                            *state_lower_names = lower_names;
-                        }
-
-                        // Update progress to show loaded state
-                        if let Ok(mut progress) = state.progress.lock() {
-                            progress.total_files = count;
-                            progress.is_complete = true;
-                        }
-                        return true;
-                    }
-                }
-                Err(_) => {}
-            }
-        }
-        Err(_) => {}
+fn get_entries_modified_between(
+    start_secs: u64,
+    end_secs: u64,
+    state: State<'_, IndexState>,
+) -> Result<Vec<IndexEntry>, String> {
+    entries_in_time_range(&state, start_secs, end_secs, |e| e.modified_secs)
+}
+
+#[tauri::command]
+fn get_entries_created_between(
+    start_secs: u64,
+    end_secs: u64,
+    state: State<'_, IndexState>,
+) -> Result<Vec<IndexEntry>, String> {
+    entries_in_time_range(&state, start_secs, end_secs, |e| e.created_secs)
+}
+
+#[tauri::command]
+fn get_path_depth_relative_to_index(path: String, state: State<'_, IndexState>) -> Option<usize> {
+    let is_complete = state.progress.lock().map(|p| p.is_complete).unwrap_or(false);
+    if !is_complete {
+        return None;
     }
-    false
+
+    let roots = state.roots.lock().ok()?;
+    let path = Path::new(&path);
+    let root = root_for_path(&roots, path)?;
+    Some(path.components().count() - root.component_count)
 }
 
 #[tauri::command]
 fn get_index_count(state: State<'_, IndexState>) -> usize {
-    state.entries.lock()
+    state.entries.read()
         .map(|e| e.len())
         .unwrap_or(0)
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct IndexStats {
+    pub total_entries: usize,
+    pub total_files: usize,
+    pub total_directories: usize,
+    pub index_size_bytes: Option<u64>,
+    // Most common file extensions, most frequent first
+    pub top_extensions: Vec<(String, usize)>,
+    // Components in the deepest indexed path
+    pub deepest_path_length: usize,
+    pub index_path: String,
+}
+
+// Only the index file's size touches the disk
+#[tauri::command]
+fn get_index_stats(state: State<'_, IndexState>) -> IndexStats {
+    const TOP_EXTENSIONS: usize = 10;
+
+    let mut total_directories = 0;
+    let mut deepest_path_length = 0;
+    let mut extension_counts: HashMap<&str, usize> = HashMap::new();
+    let guard = state.entries.read();
+    let entries: &[IndexEntry] = guard.as_deref().map(Vec::as_slice).unwrap_or_default();
+
+    for entry in entries {
+        if entry.is_directory {
+            total_directories += 1;
+        } else if !entry.extension.is_empty() {
+            *extension_counts.entry(entry.extension.as_str()).or_default() += 1;
+        }
+        deepest_path_length = deepest_path_length.max(Path::new(&entry.path).components().count());
+    }
+
+    let mut top_extensions: Vec<(String, usize)> = extension_counts.into_iter()
+        .map(|(extension, count)| (extension.to_string(), count))
+        .collect();
+    top_extensions.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    top_extensions.truncate(TOP_EXTENSIONS);
+
+    // Until it's migrated, the index on disk may still be the JSON file
+    let index_path = if !get_index_path().exists() && get_legacy_index_path().exists() {
+        get_legacy_index_path()
+    } else {
+        get_index_path()
+    };
+
+    IndexStats {
+        total_entries: entries.len(),
+        total_files: entries.len() - total_directories,
+        total_directories,
+        index_size_bytes: fs::metadata(&index_path).ok().map(|m| m.len()),
+        top_extensions,
+        deepest_path_length,
+        index_path: index_path.to_string_lossy().to_string(),
+    }
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
@@ -552,7 +6560,19 @@ pub fn run() {
         .plugin(tauri_plugin_global_shortcut::Builder::new().build())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_dialog::init())
-        .manage(IndexState::default())
+        .manage(IndexState {
+            search_history: Mutex::new(SearchHistory::load()),
+            ..IndexState::default()
+        })
+        .manage(WatcherState::default())
+        .manage(TabState {
+            tab_access_order: Mutex::new(load_config().active_tab_order.into()),
+        })
+        .setup(|app| {
+            spawn_index_autosave(app.handle().clone());
+            spawn_search_history_autosave(app.handle().clone());
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             read_directory,
             get_parent_path,
@@ -567,7 +6587,456 @@ pub fn run() {
             search_index,
             load_saved_index,
             get_index_count,
+            get_temp_dir_contents,
+            clear_temp_file,
+            get_path_access_time,
+            get_stale_index_entries,
+            get_directory_timestamps,
+            get_extended_attributes,
+            convert_path_format,
+            get_search_relevance_explanation,
+            get_path_traversal_safe,
+            list_directory_trees,
+            get_file_diff,
+            get_mount_point,
+            get_filesystem_type,
+            get_home_subdirectories,
+            get_path_type,
+            get_dir_entry_histogram,
+            get_config_diff,
+            get_entries_with_extension_change,
+            get_path_watch_status,
+            get_all_watch_statuses,
+            get_children_sorted_by_size,
+            get_index_coverage,
+            get_path_relative_to_home,
+            get_path_display_name,
+            check_dependencies,
+            search_index_by_path_segment,
+            get_file_last_access_delta,
+            get_file_age_secs,
+            get_entries_modified_between,
+            get_entries_created_between,
+            get_path_depth_relative_to_index,
+            record_tab_access,
+            get_tab_access_order,
+            get_search_stats,
+            clear_search_stats,
+            get_all_search_history_with_timestamps,
+            prune_search_history_before,
+            get_search_history_for_date,
+            get_mount_points,
+            get_config_migration_needed,
+            migrate_config,
+            get_terminal_preview,
+            get_directory_clone_time_estimate,
+            index_pinned_bookmarks,
+            reindex_all_bookmarks,
+            get_path_is_excluded,
+            get_app_data_usage,
+            get_path_security_context,
+            get_cwd,
+            set_cwd,
+            get_unique_extensions_in_dir,
+            get_path_uri,
+            get_path_from_uri,
+            get_recently_indexed_count_by_folder,
+            detect_encoding_and_transcode,
+            get_directory_change_count,
+            reset_directory_change_count,
+            get_largest_directories_by_file_count,
+            get_path_traversal_depth,
+            get_entries_added_since_timestamp,
+            get_tab_duplicate_paths,
+            get_tabs_for_path,
+            get_index_staleness_score,
+            get_path_last_commit_message,
+            get_path_git_blame_line,
+            get_entry_neighbors,
+            get_paths_in_clipboard,
+            get_file_is_binary,
+            get_path_normalized,
+            get_default_skip_directories,
+            list_running_processes,
+            cancel_indexing,
+            search_index_advanced,
+            get_file_preview_lines,
+            index_and_search,
+            get_file_checksum_batch,
+            create_directory,
+            rename_entry,
+            delete_entry,
+            copy_entry,
+            move_entry,
+            get_search_history,
+            clear_search_history,
+            list_bookmarks,
+            add_bookmark,
+            remove_bookmark,
+            reorder_bookmarks,
+            record_navigation,
+            get_recent_paths,
+            clear_recent_paths,
+            get_directory_metadata,
+            read_directory_sorted,
+            read_directory_recursive,
+            migrate_index_format,
+            start_watching,
+            stop_watching,
+            get_file_info,
+            search_by_path,
+            reindex_directory,
+            get_index_stats,
+            path_info,
+            get_config_version,
+            reset_config,
+            validate_config,
+            get_drives,
+            search_index_regex,
+            compute_directory_size,
+            open_with_default_app,
+            open_in_terminal,
+            copy_path_to_clipboard,
+            check_index_integrity,
+            repair_index,
+            get_path_breadcrumbs,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A fresh directory under the system temp dir, removed when dropped
+    struct TestDir(PathBuf);
+
+    impl TestDir {
+        fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!("spyglass-test-{}-{}", name, std::process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+            TestDir(dir)
+        }
+    }
+
+    impl Drop for TestDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn write_file_atomic_replaces_destination_and_removes_temp() {
+        let dir = TestDir::new("atomic-replace");
+        let dest = dir.0.join("config.json");
+        fs::write(&dest, "old").unwrap();
+
+        write_file_atomic(&dest, b"new").unwrap();
+
+        assert_eq!(fs::read_to_string(&dest).unwrap(), "new");
+        assert!(!atomic_temp_path(&dest).unwrap().exists());
+    }
+
+    #[test]
+    fn write_file_atomic_keeps_destination_when_rename_fails() {
+        let dir = TestDir::new("atomic-rename-fails");
+        // Renaming a file over a non-empty directory fails on every platform
+        let dest = dir.0.join("occupied");
+        fs::create_dir_all(dest.join("child")).unwrap();
+
+        assert!(write_file_atomic(&dest, b"new").is_err());
+        assert!(dest.join("child").is_dir());
+        assert!(!atomic_temp_path(&dest).unwrap().exists());
+    }
+
+    #[test]
+    fn config_reader_ignores_leftover_partial_temp_file() {
+        let dir = TestDir::new("config-partial-temp");
+        let path = dir.0.join("config.json");
+        fs::write(&path, r#"{"config_version": 1, "remember_location": true}"#).unwrap();
+        // What an interrupted write leaves behind
+        fs::write(atomic_temp_path(&path).unwrap(), r#"{"config_version": 1, "remem"#).unwrap();
+
+        let raw = read_raw_config_from(&path).unwrap();
+        assert_eq!(raw["remember_location"], true);
+    }
+
+    #[test]
+    fn config_reader_never_sees_partial_write() {
+        let dir = TestDir::new("config-concurrent");
+        let path = dir.0.join("config.json");
+        let small = serde_json::to_string(&Config::default()).unwrap();
+        // Large enough that a non-atomic write would be observable half-done
+        let large = serde_json::to_string(&Config {
+            exclude_patterns: (0..5000).map(|i| format!("pattern-{}", i)).collect(),
+            ..Config::default()
+        })
+        .unwrap();
+        write_file_atomic(&path, small.as_bytes()).unwrap();
+
+        let done = Arc::new(AtomicBool::new(false));
+        let reader = {
+            let path = path.clone();
+            let done = Arc::clone(&done);
+            thread::spawn(move || {
+                let mut reads = 0;
+                while !done.load(Ordering::Relaxed) {
+                    assert!(read_raw_config_from(&path).is_some(), "read a partial config");
+                    reads += 1;
+                }
+                reads
+            })
+        };
+        for i in 0..200 {
+            let content = if i % 2 == 0 { &large } else { &small };
+            write_file_atomic(&path, content.as_bytes()).unwrap();
+        }
+        done.store(true, Ordering::Relaxed);
+
+        assert!(reader.join().unwrap() > 0);
+    }
+
+    fn convert(path: &str, to: &str) -> Result<String, String> {
+        convert_path_format(path.to_string(), to.to_string())
+    }
+
+    #[test]
+    fn convert_path_format_handles_unc_paths() {
+        let unc = r"\\server\share\dir\file.txt";
+        assert_eq!(convert(unc, "unix").unwrap(), "//server/share/dir/file.txt");
+        assert_eq!(convert(unc, "windows").unwrap(), unc);
+        assert_eq!(convert(unc, "file_uri").unwrap(), "file://server/share/dir/file.txt");
+        assert_eq!(convert(r"\\server\share", "windows").unwrap(), r"\\server\share");
+        assert!(convert(unc, "posix_wsl").is_err());
+    }
+
+    #[test]
+    fn convert_path_format_keeps_spaces() {
+        assert_eq!(convert(r"C:\Program Files\My App", "unix").unwrap(), "/Program Files/My App");
+        assert_eq!(convert(r"C:\Program Files\My App", "posix_wsl").unwrap(), "/mnt/c/Program Files/My App");
+        assert_eq!(
+            convert("/home/me/My Documents/notes.txt", "windows").unwrap(),
+            r"C:\home\me\My Documents\notes.txt"
+        );
+        assert_eq!(convert("/home/me/it's here", "shell_quoted").unwrap(), r"'/home/me/it'\''s here'");
+        assert_eq!(convert("/mnt/c/Users/me", "windows").unwrap(), r"C:\Users\me");
+    }
+
+    #[test]
+    fn convert_path_format_percent_encodes_file_uris() {
+        assert_eq!(
+            convert("/home/me/My Documents/notes.txt", "file_uri").unwrap(),
+            "file:///home/me/My%20Documents/notes.txt"
+        );
+        assert_eq!(
+            convert(r"C:\Users\me\50% off #1.txt", "file_uri").unwrap(),
+            "file:///C:/Users/me/50%25%20off%20%231.txt"
+        );
+        assert_eq!(
+            convert(r"\\server\share\My Docs\a b.txt", "file_uri").unwrap(),
+            "file://server/share/My%20Docs/a%20b.txt"
+        );
+        // Non-ASCII names are encoded byte by byte as UTF-8
+        assert_eq!(convert("/tmp/ünï", "file_uri").unwrap(), "file:///tmp/%C3%BCn%C3%AF");
+        assert!(convert("relative/path", "file_uri").is_err());
+    }
+
+    #[test]
+    fn clear_temp_file_rejects_parent_components() {
+        let dir = TestDir::new("temp-dotdot");
+        fs::create_dir_all(dir.0.join("a")).unwrap();
+
+        for target in [dir.0.join("a").join(".."), dir.0.join("."), dir.0.clone()] {
+            assert!(remove_temp_entry(&dir.0, &target).is_err(), "{}", target.display());
+        }
+        assert!(dir.0.join("a").is_dir());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn clear_temp_file_does_not_follow_symlinks() {
+        let dir = TestDir::new("temp-symlink");
+        let temp = dir.0.join("temp");
+        let outside = dir.0.join("outside");
+        fs::create_dir_all(&temp).unwrap();
+        fs::create_dir_all(&outside).unwrap();
+        fs::write(outside.join("keep.txt"), "keep").unwrap();
+
+        // A symlinked parent that points back at the temp dir can't be used to climb out of it
+        std::os::unix::fs::symlink(&temp, temp.join("loop")).unwrap();
+        assert!(remove_temp_entry(&temp, &temp.join("loop").join("..")).is_err());
+        assert!(temp.is_dir());
+
+        // A symlinked entry is unlinked; its target survives
+        std::os::unix::fs::symlink(&outside, temp.join("link")).unwrap();
+        remove_temp_entry(&temp, &temp.join("link")).unwrap();
+        assert!(fs::symlink_metadata(temp.join("link")).is_err());
+        assert!(outside.join("keep.txt").is_file());
+
+        // A symlinked parent pointing outside the temp dir doesn't qualify
+        std::os::unix::fs::symlink(&outside, temp.join("out")).unwrap();
+        assert!(remove_temp_entry(&temp, &temp.join("out").join("keep.txt")).is_err());
+        assert!(outside.join("keep.txt").is_file());
+    }
+
+    fn preview_args(command: &str) -> Vec<String> {
+        parse_preview_command(command).unwrap()
+    }
+
+    #[test]
+    fn parse_preview_command_rejects_shell_syntax() {
+        for command in [
+            "ls | rm", "ls && rm -rf ~", "ls; rm", "cat < /etc/passwd", "echo x > file", "echo $(rm)",
+            "echo `rm`", "echo $HOME", "echo %PATH%", "echo ^&", "echo !x", "ls\nrm", "echo 'a|b'",
+        ] {
+            assert!(parse_preview_command(command).is_err(), "{:?}", command);
+        }
+    }
+
+    #[test]
+    fn parse_preview_command_requires_an_allowed_program_on_path() {
+        for command in [
+            "rm -rf /", "'rm' -rf ~", "\"rm\" -rf /", "find . -delete", "xargs rm", "python -c x",
+            "sh -c ls", "./ls", "/bin/ls", r"C:\Windows\ls", "bin/cat file",
+        ] {
+            assert!(parse_preview_command(command).is_err(), "{:?}", command);
+        }
+        assert!(parse_preview_command("").is_err());
+        assert!(parse_preview_command("   ").is_err());
+    }
+
+    #[test]
+    fn parse_preview_command_refuses_git() {
+        // Repository config can make any subcommand run programs; --output writes files
+        for command in ["git status", "git log --oneline", "git diff --output=x", "git clean -fdx", "git"] {
+            assert!(parse_preview_command(command).is_err(), "{:?}", command);
+        }
+    }
+
+    #[test]
+    fn parse_preview_command_honours_quotes() {
+        assert_eq!(preview_args("ls -la 'my dir'"), ["ls", "-la", "my dir"]);
+        assert_eq!(preview_args(r#"cat "a b" c"#), ["cat", "a b", "c"]);
+        assert_eq!(preview_args(r#"echo "it's" 'say "hi"'"#), ["echo", "it's", r#"say "hi""#]);
+        assert_eq!(preview_args("echo ''"), ["echo", ""]);
+        assert_eq!(preview_args("  head   -n 5  notes.txt "), ["head", "-n", "5", "notes.txt"]);
+        assert_eq!(preview_args("cat ab'c d'e"), ["cat", "abc de"]);
+    }
+
+    #[test]
+    fn parse_preview_command_rejects_unterminated_quotes() {
+        for command in ["cat 'notes.txt", r#"cat "notes.txt"#, r#"echo "a' "#] {
+            assert_eq!(parse_preview_command(command), Err("Unterminated quote".to_string()), "{:?}", command);
+        }
+    }
+
+    #[test]
+    fn get_path_traversal_depth_counts_steps_between_paths() {
+        let cases: &[(&str, &str, Option<usize>)] = &[
+            ("/a/b/c", "/a/d", Some(3)),
+            ("/a/b", "/a/b", Some(0)),
+            ("/a/b/", "/a/b", Some(0)),
+            ("/", "/a/b", Some(2)),
+            ("/a/b", "/", Some(2)),
+            ("/a/./b/../c", "/a/c", Some(0)),
+            // Leading ".." can't be folded away, so they count like any other component
+            ("../a", "../b", Some(2)),
+            ("../../a", "../b", Some(3)),
+            (r"C:\Users\me", "c:/users/me/docs", Some(1)),
+            (r"C:\", r"C:\Windows\System32", Some(2)),
+            (r"C:\a", r"D:\a", None),
+            (r"\\server\share\a", r"\\server\share\b\c", Some(3)),
+            (r"\\server\one\a", r"\\server\two\a", None),
+            (r"\\server\share\a", r"C:\a", None),
+            ("", "/a", None),
+            ("/a", "", None),
+        ];
+        for (from, to, expected) in cases {
+            assert_eq!(get_path_traversal_depth(from.to_string(), to.to_string()), *expected, "{} -> {}", from, to);
+        }
+    }
+
+    #[test]
+    fn normalize_lexically_resolves_dot_components() {
+        let cases = [
+            ("/a/./b/../c", "/a/c"),
+            ("/a/b/", "/a/b"),
+            ("//a//b", "/a/b"),
+            ("a/b/../../c", "c"),
+            ("a/../..", ".."),
+            // Leading ".." are kept, and extra ones above the root are dropped
+            ("../a", "../a"),
+            ("../../a/./b", "../../a/b"),
+            ("/../a", "/a"),
+            ("/..", "/"),
+            ("/", "/"),
+            ("//", "/"),
+            ("a/..", ""),
+        ];
+        for (path, expected) in cases {
+            assert_eq!(normalize_lexically(Path::new(path)), PathBuf::from(expected), "{}", path);
+        }
+        assert_eq!(get_path_normalized("a/..".to_string()), ".");
+        assert_eq!(get_path_normalized("./".to_string()), ".");
+        assert_eq!(get_path_normalized(String::new()), "");
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn normalize_lexically_keeps_windows_prefixes() {
+        let cases = [
+            (r"C:\a\..\b", r"C:\b"),
+            ("C:/a/./b/", r"C:\a\b"),
+            (r"C:\..", r"C:\"),
+            (r"\\server\share\a\..\b", r"\\server\share\b"),
+            (r"\\server\share\..", r"\\server\share\"),
+        ];
+        for (path, expected) in cases {
+            assert_eq!(normalize_lexically(Path::new(path)), PathBuf::from(expected), "{}", path);
+        }
+    }
+
+    fn breadcrumbs(path: &str) -> Vec<(String, String)> {
+        get_path_breadcrumbs(path.to_string())
+            .into_iter()
+            .map(|s| (s.name, s.full_path))
+            .collect()
+    }
+
+    fn segments(expected: &[(&str, &str)]) -> Vec<(String, String)> {
+        expected.iter().map(|(n, p)| (n.to_string(), p.to_string())).collect()
+    }
+
+    #[test]
+    fn get_path_breadcrumbs_splits_into_navigable_segments() {
+        let cases: &[(&str, &[(&str, &str)])] = &[
+            ("/", &[("/", "/")]),
+            ("//", &[("/", "/")]),
+            ("/home/user/projects", &[("home", "/home"), ("user", "/home/user"), ("projects", "/home/user/projects")]),
+            ("/home/user/", &[("home", "/home"), ("user", "/home/user")]),
+            (r"C:\", &[(r"C:\", r"C:\")]),
+            (r"C:\Users\me", &[(r"C:\", r"C:\"), ("Users", r"C:\Users"), ("me", r"C:\Users\me")]),
+            ("c:/Users", &[(r"C:\", r"C:\"), ("Users", r"C:\Users")]),
+            (r"\\server\share", &[(r"\\server\share", r"\\server\share")]),
+            (r"\\server\share\docs\a", &[
+                (r"\\server\share", r"\\server\share"),
+                ("docs", r"\\server\share\docs"),
+                ("a", r"\\server\share\docs\a"),
+            ]),
+        ];
+        for (path, expected) in cases {
+            assert_eq!(breadcrumbs(path), segments(expected), "{}", path);
+        }
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn get_path_breadcrumbs_keeps_unix_names_and_relative_paths() {
+        // A backslash is part of the file name on Unix
+        assert_eq!(breadcrumbs(r"/tmp/a\b"), segments(&[("tmp", "/tmp"), (r"a\b", r"/tmp/a\b")]));
+        assert_eq!(breadcrumbs("a/b"), segments(&[("a", "a"), ("b", "a/b")]));
+        assert_eq!(breadcrumbs("../x"), segments(&[("..", ".."), ("x", "../x")]));
+    }
+}